@@ -83,14 +83,15 @@ pub fn main() {
         Alignment::corresponding,
         Alignment::corresponding,
         Alignment::corresponding,
-        // By breaking instructions into vectors, 
-        // we are able to customize the instruction for each connection.
-        // If you don't provide enough instructions to cover each connection,
+        // Instructions can be a constant, nested vectors listed by layer, row, and block,
+        // or a closure that generates them from the coordinates of each pair.
+        // If nested vectors don't provide enough instructions to cover each connection,
         // the last instruction will be repeated for the remaining connections.
         // If you don't provide any connection instructions, it will use the default.
-        // (This syntax will be made cleaner at some point.)
-        vec![vec![vec![1]]], 
+        connect::Instructions::constant(1), 
     );
+    // (Use `connect_with` and `connect::ConnectionOptions` to skip void blocks,
+    // or to leave them out of the alignment altogether.)
 
     // TBD: In the future, iterating over types will be much easier.
     cube1.get_all_mut()
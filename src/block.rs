@@ -37,7 +37,10 @@ pub trait Block: Clone + Default {
     /// Swap this block with another if the latter is a void.
     fn shift(&mut self, other: &mut Self) -> Option<()> {
         match other.is_void() {
-            true => { Some(self.swap(other)) },
+            true => { 
+                self.swap(other);
+                Some(())
+            },
             false => None
         }
     }
@@ -0,0 +1,391 @@
+
+use crate::{ Node, Stack, Aligner, Alignment, Coord };
+use super::{ Instructions, ConnectionReport, ConnectionPlan, ConnectionPolicy, ConnectionOptions, ConnectionMap };
+use super::cascade::{ cascade, index_structure };
+
+/// Filters the pairs considered for connection, by the coordinates of each block.
+type PairFilter<'i> = Box<dyn Fn(Coord, Coord) -> bool + 'i>;
+
+/// Builds a procedural connection between two stacks, or within a single stack,
+/// one setting at a time.
+/// Alignments are made over the indexes of the blocks within the stack,
+/// so aligners used here should not depend on block contents.
+/// ```
+/// use blok::{ Stack, Alignment, Layout, layout };
+/// # use blok::{ Block, Node };
+/// # #[derive(Default, Clone)]
+/// # struct MyNode { id: String, connections: Vec<String> }
+/// # impl Block for MyNode {
+/// #     type CreationInstructions = String;
+/// #     fn create(id: &String) -> Self { MyNode { id: id.clone(), connections: Vec::new() } }
+/// #     fn void() -> Self { Self::default() }
+/// #     fn is_void(&self) -> bool { self.id.is_empty() }
+/// # }
+/// # impl Node for MyNode {
+/// #     type ConnectionInstructions = ();
+/// #     fn connect(&mut self, other: &mut Self, _: &()) { self.connections.push(other.id.clone()) }
+/// # }
+///
+/// let mut stack1 = Stack::<MyNode>::new();
+/// stack1.populate(vec![layout![3; 3]; 2], &"a".to_string());
+/// let mut stack2 = stack1.clone();
+///
+/// let report = stack1.connect_to(&mut stack2)
+///     .layers(Alignment::centered)
+///     .rows(Alignment::corresponding)
+///     .blocks(Alignment::reversed)
+///     .filter(|from, _| from.0 == 0)
+///     .run();
+///
+/// assert_eq!(report.total, 9);
+/// ```
+pub struct StackConnector<'s, 'i, N: Node> {
+    source: &'s mut Stack<N>,
+    /// None when connecting within the source stack.
+    target: Option<&'s mut Stack<N>>,
+    layer_align: Aligner<Vec<Vec<usize>>>,
+    row_align: Aligner<Vec<usize>>,
+    block_align: Aligner<usize>,
+    instructions: Instructions<'i, N::ConnectionInstructions>,
+    filter: Option<PairFilter<'i>>,
+    options: ConnectionOptions,
+    map: Option<&'i mut ConnectionMap<N::ConnectionInstructions>>,
+}
+
+impl<'s, 'i, N: Node> StackConnector<'s, 'i, N> {
+
+    /// Start building a connection.
+    /// When no target is given, the source stack is connected to itself.
+    fn new(source: &'s mut Stack<N>, target: Option<&'s mut Stack<N>>) -> Self {
+        let options = match target {
+            Some(_) => ConnectionOptions::default(),
+            None => ConnectionOptions::default().stack_ids(0, 0),
+        };
+        Self {
+            source,
+            target,
+            layer_align: Alignment::corresponding,
+            row_align: Alignment::corresponding,
+            block_align: Alignment::corresponding,
+            instructions: Instructions::default(),
+            filter: None,
+            options,
+            map: None,
+        }
+    }
+
+    /// Set the aligner used to pair layers. Defaults to `Alignment::corresponding`.
+    pub fn layers(mut self, layer_align: Aligner<Vec<Vec<usize>>>) -> Self {
+        self.layer_align = layer_align;
+        self
+    }
+
+    /// Set the aligner used to pair rows. Defaults to `Alignment::corresponding`.
+    pub fn rows(mut self, row_align: Aligner<Vec<usize>>) -> Self {
+        self.row_align = row_align;
+        self
+    }
+
+    /// Set the aligner used to pair blocks. Defaults to `Alignment::corresponding`.
+    pub fn blocks(mut self, block_align: Aligner<usize>) -> Self {
+        self.block_align = block_align;
+        self
+    }
+
+    /// Set the instructions used for each connection. Defaults to the default instructions.
+    pub fn instructions(
+        mut self,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+    ) -> Self {
+        self.instructions = instructions.into();
+        self
+    }
+
+    /// Only connect pairs for which the filter returns true,
+    /// given the coordinates of the source and target blocks.
+    pub fn filter<F: Fn(Coord, Coord) -> bool + 'i>(mut self, filter: F) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Set the policy for connecting void blocks. Defaults to `ConnectionPolicy::ConnectAll`.
+    pub fn policy(mut self, policy: ConnectionPolicy) -> Self {
+        self.options.policy = policy;
+        self
+    }
+
+    /// Do not connect pairs where either block is void.
+    /// Shorthand for `policy(ConnectionPolicy::SkipVoids)`.
+    pub fn skip_voids(self) -> Self {
+        self.policy(ConnectionPolicy::SkipVoids)
+    }
+
+    /// Align blocks as though voids were removed from each row,
+    /// so that block aligners only see the non-void blocks.
+    pub fn compact_voids(mut self) -> Self {
+        self.options.compact_voids = true;
+        self
+    }
+
+    /// Set the identifiers passed to `Node::connect_at` for the source and target stacks.
+    /// Defaults to 0 for the source and 1 for the target.
+    /// When connecting within a stack, the source identifier is used for both.
+    pub fn stack_ids(mut self, source: usize, target: usize) -> Self {
+        let target = if self.target.is_some() { target } else { source };
+        self.options = self.options.stack_ids(source, target);
+        self
+    }
+
+    /// Also record the connections made in a map.
+    pub fn record(mut self, map: &'i mut ConnectionMap<N::ConnectionInstructions>) -> Self {
+        self.map = Some(map);
+        self
+    }
+
+    /// Plan the connections without making them.
+    /// When connecting within a stack, pairs that align a block with itself are left out.
+    pub fn plan(&self) -> ConnectionPlan<N::ConnectionInstructions> {
+        self.plan_and_report().0
+    }
+
+    /// Plan the connections, reporting the pairs that were left out of the plan.
+    fn plan_and_report(&self) -> (ConnectionPlan<N::ConnectionInstructions>, ConnectionReport) {
+
+        let mut source_indexes = index_structure(self.source.layouts());
+        let mut target_indexes = match &self.target {
+            Some(target) => index_structure(target.layouts()),
+            None => source_indexes.clone(),
+        };
+
+        let source = self.source.blocks();
+        let target = match &self.target {
+            Some(target) => target.blocks(),
+            None => source,
+        };
+
+        let keep_source = |i: &usize| !source[*i].is_void();
+        let keep_target = |j: &usize| !target[*j].is_void();
+        let compaction = self.options.compact_voids
+            .then_some((&keep_source as _, &keep_target as _));
+
+        let mut pairs = Vec::new();
+        let mut report = ConnectionReport::new();
+        report.skipped_out_of_range = cascade(
+            &mut source_indexes,
+            &mut target_indexes,
+            self.layer_align,
+            self.row_align,
+            self.block_align,
+            compaction,
+            |step, from, to| pairs.push((step, from, to))
+        );
+
+        // Pairs that align a block with itself are left out of the alignment, as in plan_within.
+        if self.target.is_none() {
+            let before = pairs.len();
+            pairs.retain(|(_, from, to)| from != to);
+            report.skipped_self += before - pairs.len();
+        }
+
+        let mut plan = ConnectionPlan::new();
+        for (index, (step, from, to)) in pairs.into_iter().enumerate() {
+            let a = &source[source_indexes[from.0][from.1][from.2]];
+            let b = &target[target_indexes[to.0][to.1][to.2]];
+
+            let is_filtered = self.filter.as_ref().is_some_and(|filter| !filter(from, to));
+            if is_filtered || !self.options.policy.allows(a, b) {
+                report.skipped_by_policy += 1;
+                continue
+            }
+
+            let instructions = self.instructions.get(step, from, to, index);
+            plan.add(from, to, instructions);
+        }
+
+        (plan, report)
+    }
+
+    /// Make the connections and report the results.
+    /// When connecting within a stack, pairs that align a block with itself are skipped.
+    pub fn run(self) -> ConnectionReport {
+        let (plan, mut report) = self.plan_and_report();
+        if let Some(map) = self.map {
+            map.record(&plan);
+        }
+
+        let executed = match self.target {
+            Some(target) => plan.execute_as(self.source, target, self.options.stack_ids),
+            None => plan.execute_within_as(self.source, self.options.stack_ids.0),
+        };
+
+        report.merge(&executed);
+        report
+    }
+
+}
+
+impl<N: Node> Stack<N> {
+
+    /// Begin building a connection from this stack to another.
+    pub fn connect_to<'s, 'i>(&'s mut self, other: &'s mut Self) -> StackConnector<'s, 'i, N> {
+        StackConnector::new(self, Some(other))
+    }
+
+    /// Begin building a connection between the blocks of this stack.
+    /// ```
+    /// use blok::{ Stack, Alignment, Layout, layout };
+    /// # use blok::{ Block, Node };
+    /// # #[derive(Default, Clone)]
+    /// # struct MyNode { connections: usize }
+    /// # impl Block for MyNode {
+    /// #     type CreationInstructions = ();
+    /// #     fn create(_: &()) -> Self { MyNode { connections: 0 } }
+    /// #     fn void() -> Self { Self::default() }
+    /// #     fn is_void(&self) -> bool { false }
+    /// # }
+    /// # impl Node for MyNode {
+    /// #     type ConnectionInstructions = ();
+    /// #     fn connect(&mut self, _: &mut Self, _: &()) { self.connections += 1 }
+    /// # }
+    ///
+    /// let mut stack = Stack::<MyNode>::new();
+    /// stack.populate(vec![layout![2; 2]; 3], &());
+    ///
+    /// // Connect each layer to the one above it.
+    /// let report = stack.connect_within()
+    ///     .rows(Alignment::corresponding)
+    ///     .blocks(Alignment::corresponding)
+    ///     .filter(|from, to| to.0 == from.0 + 1)
+    ///     .layers(Alignment::dense)
+    ///     .run();
+    ///
+    /// assert_eq!(report.total, 8);
+    /// ```
+    pub fn connect_within<'s, 'i>(&'s mut self) -> StackConnector<'s, 'i, N> {
+        StackConnector::new(self, None)
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::{ Block, Stack, Layout, layout, Alignment };
+    use crate::types::stack::test::test_cube;
+    use crate::connect::{ Instructions, ConnectionMap };
+    use crate::node::Address;
+    use crate::node::test::AddressBlock;
+
+
+    /// Test building a connection between two stacks.
+    #[test] fn connect_to_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        let report = cube1.connect_to(&mut cube2)
+            .blocks(Alignment::reversed)
+            .instructions(Instructions::constant(1))
+            .run();
+
+        assert_eq!(report.total, 8);
+        let connections: Vec<&str> = cube1.blocks().iter()
+            .map(|b| b.connections[0].as_str())
+            .collect();
+        assert_eq!(connections, vec!["1", "0", "3", "2", "5", "4", "7", "6"]);
+    }
+
+    /// Test building a connection within a single stack.
+    #[test] fn connect_within_test() {
+        let mut cube = test_cube();
+        let report = cube.connect_within()
+            .layers(Alignment::reversed)
+            .instructions(Instructions::constant(1))
+            .run();
+
+        assert_eq!(report.total, 8);
+        assert_eq!(cube.blocks()[0].connections, vec!["4".to_string()]);
+        assert_eq!(cube.blocks()[4].connections, vec!["0".to_string()]);
+
+        // Blocks are not connected to themselves.
+        let mut cube = test_cube();
+        let report = cube.connect_within()
+            .instructions(Instructions::constant(1))
+            .run();
+        assert_eq!(report.total, 0);
+        assert_eq!(report.skipped_self, 8);
+        assert_eq!(report.skipped_by_policy, 0);
+    }
+
+    /// Test skipping void blocks and filtering pairs.
+    #[test] fn connect_filter_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        cube2.blocks_mut()[0].to_void();
+
+        let report = cube1.connect_to(&mut cube2)
+            .instructions(Instructions::constant(1))
+            .skip_voids()
+            .filter(|from, _| from.0 == 0)
+            .run();
+
+        assert_eq!(report.total, 3);
+        assert_eq!(report.skipped_by_policy, 5);
+        assert_eq!(report.per_row[&(0, 1)], 2);
+        assert!(cube1.blocks()[0].connections.is_empty());
+        assert!(cube1.blocks()[4].connections.is_empty());
+    }
+
+    /// Test leaving voids out of the alignment.
+    #[test] fn connect_compact_voids_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        cube2.blocks_mut()[0].to_void();
+
+        let report = cube1.connect_to(&mut cube2)
+            .instructions(Instructions::constant(1))
+            .compact_voids()
+            .run();
+
+        assert_eq!(report.total, 7);
+        assert_eq!(cube1.blocks()[0].connections, vec!["1".to_string()]);
+        assert!(cube1.blocks()[1].connections.is_empty());
+    }
+
+    /// Test recording connections in a map.
+    #[test] fn connect_record_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        let mut map = ConnectionMap::new();
+        cube1.connect_to(&mut cube2)
+            .layers(Alignment::reversed)
+            .instructions(Instructions::generate(|_, from, _| from.2 as u8 + 1))
+            .record(&mut map)
+            .run();
+
+        assert_eq!(map.len(), 8);
+        assert_eq!(map.get((0, 0, 1), (1, 0, 1)), Some(&2));
+        assert_eq!(map.incoming((0, 1, 0)).collect::<Vec<_>>(), vec![((1, 1, 0), &1)]);
+    }
+
+    /// Test that blocks are given their addresses when connected.
+    #[test] fn connect_at_test() {
+        let mut stack1 = Stack::<AddressBlock>::new();
+        stack1.populate(vec![layout![1, 2]], &());
+        let mut stack2 = stack1.clone();
+
+        stack1.connect_to(&mut stack2)
+            .blocks(Alignment::reversed)
+            .stack_ids(3, 7)
+            .run();
+
+        assert_eq!(stack1.blocks()[1].links, vec![
+            (Address::new(3, (0, 1, 0)), Address::new(7, (0, 1, 1)))
+        ]);
+
+        stack1.connect_within()
+            .blocks(Alignment::reversed)
+            .stack_ids(5, 9)
+            .run();
+
+        assert_eq!(stack1.blocks()[2].links[1], (Address::new(5, (0, 1, 1)), Address::new(5, (0, 1, 0))));
+    }
+
+}
+
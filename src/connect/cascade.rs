@@ -0,0 +1,273 @@
+
+use crate::{ Aligner, Alignment, Coord, Layout };
+
+/// Decides which elements of the first and second structure take part in block alignment,
+/// so that alignment can run over a compacted row.
+pub(crate) type Compaction<'k, T> = (&'k dyn Fn(&T) -> bool, &'k dyn Fn(&T) -> bool);
+
+/// Organizes the indexes of a stack's blocks by layer and row,
+/// so that alignments can be made without borrowing the blocks themselves.
+pub(crate) fn index_structure(layouts: &[Layout]) -> Vec<Vec<Vec<usize>>> {
+
+    let mut index = 0usize;
+    layouts.iter()
+        .map(|layout| {
+            layout.iter()
+                .map(|r| {
+                    let row = (index..index + r).collect();
+                    index += r;
+                    row
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Aligns layers, then the rows of each aligned layer pair,
+/// then the blocks of each aligned row pair,
+/// visiting each block pair with its alignment step and the coordinates of both blocks.
+/// Pairs whose indices fall outside of either structure are skipped,
+/// and the number skipped at every level is returned.
+/// Structures are only modified temporarily, when compacting rows for alignment.
+pub(crate) fn cascade<T>(
+    stack1: &mut Vec<Vec<Vec<T>>>,
+    stack2: &mut Vec<Vec<Vec<T>>>,
+    layer_align: Aligner<Vec<Vec<T>>>,
+    row_align: Aligner<Vec<T>>,
+    block_align: Aligner<T>,
+    compaction: Option<Compaction<T>>,
+    mut visit: impl FnMut(Coord, Coord, Coord)
+) -> usize {
+
+    let mut skipped = 0usize;
+    let layer_alignment = layer_align(stack1, stack2);
+    for (l_step, &(l1, l2)) in layer_alignment.iter().enumerate() {
+        if stack1.len() <= l1 || stack2.len() <= l2 {
+            skipped += 1;
+            continue
+        }
+
+        let row_alignment = row_align(&stack1[l1], &stack2[l2]);
+        for (r_step, &(r1, r2)) in row_alignment.iter().enumerate() {
+            if stack1[l1].len() <= r1 || stack2[l2].len() <= r2 {
+                skipped += 1;
+                continue
+            }
+
+            let block_alignment = align_blocks(
+                &mut stack1[l1][r1],
+                &mut stack2[l2][r2],
+                block_align,
+                compaction
+            );
+
+            for (b_step, (b1, b2)) in block_alignment.into_iter().enumerate() {
+                if let (Some(b1), Some(b2)) = (b1, b2) {
+                    visit((l_step, r_step, b_step), (l1, r1, b1), (l2, r2, b2))
+                } else {
+                    skipped += 1;
+                }
+            }
+        }
+    }
+
+    skipped
+}
+
+/// Runs the cascade over a single pair of layers, treating each as a stack of one layer.
+pub(crate) fn cascade_layers<T>(
+    layer1: &mut Vec<Vec<T>>,
+    layer2: &mut Vec<Vec<T>>,
+    row_align: Aligner<Vec<T>>,
+    block_align: Aligner<T>,
+    compaction: Option<Compaction<T>>,
+    visit: impl FnMut(Coord, Coord, Coord)
+) -> usize {
+
+    let mut stack1 = vec![std::mem::take(layer1)];
+    let mut stack2 = vec![std::mem::take(layer2)];
+
+    let skipped = cascade(
+        &mut stack1,
+        &mut stack2,
+        |_, _| Alignment::wrap(vec![(0, 0)]),
+        row_align,
+        block_align,
+        compaction,
+        visit
+    );
+
+    *layer1 = stack1.pop().expect("Layer is restored");
+    *layer2 = stack2.pop().expect("Layer is restored");
+    skipped
+}
+
+/// Runs the cascade over a single pair of rows, treating each as a stack of one row.
+pub(crate) fn cascade_rows<T>(
+    row1: &mut Vec<T>,
+    row2: &mut Vec<T>,
+    block_align: Aligner<T>,
+    compaction: Option<Compaction<T>>,
+    visit: impl FnMut(Coord, Coord, Coord)
+) -> usize {
+
+    let mut layer1 = vec![std::mem::take(row1)];
+    let mut layer2 = vec![std::mem::take(row2)];
+
+    let skipped = cascade_layers(
+        &mut layer1,
+        &mut layer2,
+        |_, _| Alignment::wrap(vec![(0, 0)]),
+        block_align,
+        compaction,
+        visit
+    );
+
+    *row1 = layer1.pop().expect("Row is restored");
+    *row2 = layer2.pop().expect("Row is restored");
+    skipped
+}
+
+/// Aligns the blocks of two rows, returning the index pairs in alignment order.
+/// Indices that fall outside of either row are given as None.
+/// When compacting, alignment runs over the kept elements only,
+/// and the resulting indices are mapped back to their positions in the full rows.
+fn align_blocks<T>(
+    row1: &mut Vec<T>,
+    row2: &mut Vec<T>,
+    block_align: Aligner<T>,
+    compaction: Option<Compaction<T>>
+) -> Vec<(Option<usize>, Option<usize>)> {
+
+    let Some((keep1, keep2)) = compaction else {
+        return block_align(row1, row2).iter()
+            .map(|&(b1, b2)| {
+                ((b1 < row1.len()).then_some(b1), (b2 < row2.len()).then_some(b2))
+            })
+            .collect()
+    };
+
+    let (kept1, positions1, dropped1) = compact_helper(std::mem::take(row1), keep1);
+    let (kept2, positions2, dropped2) = compact_helper(std::mem::take(row2), keep2);
+
+    let alignment = block_align(&kept1, &kept2).iter()
+        .map(|&(b1, b2)| (positions1.get(b1).copied(), positions2.get(b2).copied()))
+        .collect();
+
+    *row1 = restore_helper(kept1, positions1, dropped1);
+    *row2 = restore_helper(kept2, positions2, dropped2);
+
+    alignment
+}
+
+/// Separates the kept elements of a row from the rest,
+/// recording the original position of each.
+fn compact_helper<T>(
+    row: Vec<T>,
+    keep: &dyn Fn(&T) -> bool
+) -> (Vec<T>, Vec<usize>, Vec<(usize, T)>) {
+
+    let mut kept = Vec::new();
+    let mut positions = Vec::new();
+    let mut dropped = Vec::new();
+
+    for (b, element) in row.into_iter().enumerate() {
+        if keep(&element) {
+            kept.push(element);
+            positions.push(b);
+        } else {
+            dropped.push((b, element));
+        }
+    }
+
+    (kept, positions, dropped)
+}
+
+/// Merges kept and dropped elements back into their original order.
+fn restore_helper<T>(
+    kept: Vec<T>,
+    positions: Vec<usize>,
+    dropped: Vec<(usize, T)>
+) -> Vec<T> {
+
+    let mut row: Vec<(usize, T)> = positions.into_iter()
+        .zip(kept)
+        .chain(dropped)
+        .collect();
+
+    row.sort_by_key(|(b, _)| *b);
+    row.into_iter().map(|(_, element)| element).collect()
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+    use crate::{ layout, Alignment };
+
+    /// Test that block indexes are organized by layout.
+    #[test] fn index_structure_test() {
+        let indexes = index_structure(&[layout![1, 2], layout![0, 1]]);
+        assert_eq!(indexes, vec![vec![vec![0], vec![1, 2]], vec![vec![], vec![3]]]);
+    }
+
+    /// Test that the cascade visits aligned pairs and skips missing indices.
+    #[test] fn cascade_test() {
+        let mut indexes1 = index_structure(&[layout![1, 2]]);
+        let mut indexes2 = index_structure(&[layout![2, 1], layout![1]]);
+
+        let mut pairs = Vec::new();
+        cascade(
+            &mut indexes1,
+            &mut indexes2,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Alignment::dense,
+            None,
+            |_, a, b| pairs.push((a, b))
+        );
+
+        assert_eq!(pairs, vec![
+            ((0, 0, 0), (0, 0, 0)),
+            ((0, 0, 0), (0, 0, 1)),
+            ((0, 1, 0), (0, 1, 0)),
+            ((0, 1, 1), (0, 1, 0)),
+        ]);
+
+        // Out-of-range pairs are counted at each level.
+        let skipped = cascade(
+            &mut indexes1,
+            &mut indexes2,
+            |_, _| Alignment::wrap(vec![(0, 0), (0, 2)]),
+            |_, _| Alignment::wrap(vec![(0, 0), (1, 4)]),
+            |_, _| Alignment::wrap(vec![(0, 0), (3, 0)]),
+            None,
+            |_, _, _| {}
+        );
+        assert_eq!(skipped, 3);
+    }
+
+    /// Test that compacted rows align over kept elements and are restored afterward.
+    #[test] fn compaction_test() {
+        let mut row1 = vec![0, 1, 0, 2];
+        let mut row2 = vec![3, 0, 4];
+        let keep = |v: &i32| *v != 0;
+
+        let mut pairs = Vec::new();
+        cascade_rows(
+            &mut row1,
+            &mut row2,
+            Alignment::corresponding,
+            Some((&keep, &keep)),
+            |_, a, b| pairs.push((a.2, b.2))
+        );
+
+        assert_eq!(pairs, vec![(1, 0), (3, 2)]);
+        assert_eq!(row1, vec![0, 1, 0, 2]);
+        assert_eq!(row2, vec![3, 0, 4]);
+    }
+
+}
+
@@ -0,0 +1,308 @@
+
+use std::collections::{ BTreeMap, BTreeSet, VecDeque, btree_map::Entry };
+
+use crate::{ Block, Stack, Coord };
+use super::ConnectionMap;
+
+/// Traversal of the graph formed by recorded connections.
+/// Blocks are identified by their coordinates, and only blocks with connections are visited,
+/// apart from the block where a traversal starts.
+impl<I> ConnectionMap<I> {
+
+    /// List blocks reachable from the start, in breadth-first order, starting with the start.
+    /// Neighbors are visited in coordinate order.
+    pub fn bfs(&self, start: Coord) -> Vec<Coord> {
+        let mut visited = BTreeSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut order = Vec::new();
+
+        while let Some(coord) = queue.pop_front() {
+            order.push(coord);
+            for (next, _) in self.outgoing(coord) {
+                if visited.insert(next) { queue.push_back(next) }
+            }
+        }
+
+        order
+    }
+
+    /// List blocks reachable from the start, in depth-first preorder, starting with the start.
+    /// Neighbors are visited in coordinate order.
+    pub fn dfs(&self, start: Coord) -> Vec<Coord> {
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![start];
+        let mut order = Vec::new();
+
+        while let Some(coord) = stack.pop() {
+            if !visited.insert(coord) { continue }
+            order.push(coord);
+
+            let next: Vec<Coord> = self.outgoing(coord).map(|(to, _)| to).collect();
+            stack.extend(next.into_iter().rev().filter(|to| !visited.contains(to)));
+        }
+
+        order
+    }
+
+    /// Get the set of blocks reachable from the start, including the start itself.
+    pub fn reachable(&self, start: Coord) -> BTreeSet<Coord> {
+        self.bfs(start).into_iter().collect()
+    }
+
+    /// Check whether a block can be reached from another by following connections.
+    pub fn is_reachable(&self, from: Coord, to: Coord) -> bool {
+        self.distances(from).contains_key(&to)
+    }
+
+    /// Count the fewest connections needed to reach each reachable block from the start.
+    pub fn distances(&self, start: Coord) -> BTreeMap<Coord, usize> {
+        let mut distances = BTreeMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(coord) = queue.pop_front() {
+            let distance = distances[&coord];
+            for (next, _) in self.outgoing(coord) {
+                distances.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    distance + 1
+                });
+            }
+        }
+
+        distances
+    }
+
+    /// Find a path with the fewest connections between two blocks, including both ends.
+    /// Returns None if the target cannot be reached.
+    /// ```
+    /// use blok::connect::ConnectionMap;
+    ///
+    /// let mut map = ConnectionMap::new();
+    /// map.connect((0, 0, 0), (1, 0, 0), ())
+    ///     .connect((1, 0, 0), (2, 0, 0), ())
+    ///     .connect((0, 0, 0), (2, 0, 0), ());
+    ///
+    /// assert_eq!(map.shortest_path((0, 0, 0), (2, 0, 0)), Some(vec![(0, 0, 0), (2, 0, 0)]));
+    /// assert_eq!(map.shortest_path((2, 0, 0), (0, 0, 0)), None);
+    /// ```
+    pub fn shortest_path(&self, from: Coord, to: Coord) -> Option<Vec<Coord>> {
+        let mut previous = BTreeMap::from([(from, from)]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(coord) = queue.pop_front() {
+            if coord == to { break }
+            for (next, _) in self.outgoing(coord) {
+                if let Entry::Vacant(entry) = previous.entry(next) {
+                    entry.insert(coord);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        previous.get(&to)?;
+        let mut path = vec![to];
+        while let Some(&last) = path.last() {
+            if last == from { break }
+            path.push(previous[&last]);
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// Group connected blocks into strongly connected components,
+    /// where every block in a component can reach every other.
+    /// Components are given in topological order of the graph they condense into,
+    /// and the blocks within each are sorted by coordinates.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<Coord>> {
+
+        // Order blocks by the time their depth-first search finishes.
+        let mut visited = BTreeSet::new();
+        let mut finished = Vec::new();
+        for start in self.coords() {
+            if !visited.insert(start) { continue }
+            let mut stack = vec![(start, self.targets(start))];
+            while let Some((coord, next)) = stack.last_mut() {
+                if let Some(to) = next.pop() {
+                    if visited.insert(to) { stack.push((to, self.targets(to))) }
+                } else {
+                    finished.push(*coord);
+                    stack.pop();
+                }
+            }
+        }
+
+        // Collect the blocks that reach each root, in reverse finishing order.
+        let mut assigned = BTreeSet::new();
+        let mut components = Vec::new();
+        for root in finished.into_iter().rev() {
+            if !assigned.insert(root) { continue }
+            let mut component = vec![root];
+            let mut stack = vec![root];
+            while let Some(coord) = stack.pop() {
+                for (from, _) in self.incoming(coord) {
+                    if assigned.insert(from) {
+                        component.push(from);
+                        stack.push(from);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Check whether any block can reach itself by following connections.
+    pub fn has_cycle(&self) -> bool {
+        self.edges().any(|(from, to, _)| from == to) ||
+        self.strongly_connected_components().iter().any(|c| c.len() > 1)
+    }
+
+    /// Order the connected blocks so that each comes before the blocks it connects to,
+    /// for evaluating feed-forward networks.
+    /// Ties are broken by coordinates. Returns an error if the connections contain a cycle.
+    /// ```
+    /// use blok::connect::ConnectionMap;
+    ///
+    /// let mut map = ConnectionMap::new();
+    /// map.connect((1, 0, 0), (2, 0, 0), ())
+    ///     .connect((0, 0, 0), (1, 0, 0), ());
+    /// assert_eq!(map.topological_order().unwrap(), vec![(0, 0, 0), (1, 0, 0), (2, 0, 0)]);
+    ///
+    /// map.connect((2, 0, 0), (0, 0, 0), ());
+    /// assert!(map.topological_order().is_err());
+    /// ```
+    pub fn topological_order(&self) -> anyhow::Result<Vec<Coord>> {
+        let coords = self.coords();
+        let mut in_degree: BTreeMap<Coord, usize> = coords.iter()
+            .map(|&coord| (coord, self.incoming(coord).count()))
+            .collect();
+
+        let mut ready: BTreeSet<Coord> = in_degree.iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&coord, _)| coord)
+            .collect();
+
+        let mut order = Vec::new();
+        while let Some(coord) = ready.pop_first() {
+            order.push(coord);
+            for (to, _) in self.outgoing(coord) {
+                let degree = in_degree.get_mut(&to).expect("Target is a connected block");
+                *degree -= 1;
+                if *degree == 0 { ready.insert(to); }
+            }
+        }
+
+        if order.len() < coords.len() {
+            return Err(anyhow::anyhow!("Connections contain a cycle"))
+        }
+
+        Ok(order)
+    }
+
+    /// List the blocks connected from a block, in reverse coordinate order.
+    fn targets(&self, coord: Coord) -> Vec<Coord> {
+        let mut targets: Vec<Coord> = self.outgoing(coord).map(|(to, _)| to).collect();
+        targets.reverse();
+        targets
+    }
+
+}
+
+impl<B: Block> Stack<B> {
+
+    /// Build a map of the connections stored in the blocks of this stack,
+    /// given a function that lists the coordinates each block is connected to.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// # use blok::Block;
+    /// # #[derive(Default, Clone)]
+    /// # struct MyBlock { targets: Vec<(usize, usize, usize)> }
+    /// # impl Block for MyBlock {
+    /// #     type CreationInstructions = ();
+    /// #     fn create(_: &()) -> Self { Self::default() }
+    /// #     fn void() -> Self { Self::default() }
+    /// #     fn is_void(&self) -> bool { false }
+    /// # }
+    ///
+    /// let mut stack = Stack::<MyBlock>::new();
+    /// stack.populate(vec![layout![1]; 3], &());
+    /// stack.get_block_mut(0, 0, 0).unwrap().targets = vec![(1, 0, 0)];
+    /// stack.get_block_mut(1, 0, 0).unwrap().targets = vec![(2, 0, 0)];
+    ///
+    /// let map = stack.connection_map(|block| block.targets.clone());
+    /// assert!(map.is_reachable((0, 0, 0), (2, 0, 0)));
+    /// ```
+    pub fn connection_map(&self, neighbors: impl Fn(&B) -> Vec<Coord>) -> ConnectionMap<()> {
+        let mut map = ConnectionMap::new();
+        for (l, layer) in self.get_all_ref().into_iter().enumerate() {
+            for (r, row) in layer.into_iter().enumerate() {
+                for (b, block) in row.into_iter().enumerate() {
+                    for to in neighbors(block) {
+                        map.connect((l, r, b), to, ());
+                    }
+                }
+            }
+        }
+        map
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+
+    /// Diamond from (0, 0, 0) to (2, 0, 0), with a detached cycle.
+    fn test_map() -> ConnectionMap<()> {
+        let mut map = ConnectionMap::new();
+        map.connect((0, 0, 0), (1, 0, 0), ())
+            .connect((0, 0, 0), (1, 0, 1), ())
+            .connect((1, 0, 0), (2, 0, 0), ())
+            .connect((1, 0, 1), (2, 0, 0), ())
+            .connect((3, 0, 0), (3, 0, 1), ())
+            .connect((3, 0, 1), (3, 0, 0), ());
+        map
+    }
+
+    /// Test breadth-first and depth-first traversal.
+    #[test] fn traversal_test() {
+        let map = test_map();
+        assert_eq!(map.bfs((0, 0, 0)), vec![(0, 0, 0), (1, 0, 0), (1, 0, 1), (2, 0, 0)]);
+        assert_eq!(map.dfs((0, 0, 0)), vec![(0, 0, 0), (1, 0, 0), (2, 0, 0), (1, 0, 1)]);
+        assert_eq!(map.reachable((1, 0, 1)).len(), 2);
+        assert_eq!(map.distances((0, 0, 0))[&(2, 0, 0)], 2);
+        assert!(!map.is_reachable((0, 0, 0), (3, 0, 0)));
+        assert_eq!(map.shortest_path((0, 0, 0), (0, 0, 0)), Some(vec![(0, 0, 0)]));
+        assert_eq!(
+            map.shortest_path((0, 0, 0), (2, 0, 0)),
+            Some(vec![(0, 0, 0), (1, 0, 0), (2, 0, 0)])
+        );
+    }
+
+    /// Test strongly connected components and cycle detection.
+    #[test] fn components_test() {
+        let mut map = test_map();
+        let components = map.strongly_connected_components();
+        assert_eq!(components.len(), 5);
+        assert!(components.contains(&vec![(3, 0, 0), (3, 0, 1)]));
+        assert!(map.has_cycle());
+        assert!(map.topological_order().is_err());
+
+        map.disconnect((3, 0, 1), (3, 0, 0));
+        assert!(!map.has_cycle());
+        assert_eq!(map.topological_order().unwrap(), vec![
+            (0, 0, 0), (1, 0, 0), (1, 0, 1), (2, 0, 0), (3, 0, 0), (3, 0, 1)
+        ]);
+
+        map.connect((2, 0, 0), (2, 0, 0), ());
+        assert!(map.has_cycle());
+    }
+
+}
+
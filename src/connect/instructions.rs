@@ -0,0 +1,141 @@
+
+use crate::Coord;
+
+/// Supplies the instructions used for each connection made between aligned blocks.
+/// Instructions can be given as a constant, as nested vectors,
+/// or generated for each pair by a closure.
+pub enum Instructions<'i, I> {
+    /// Use the same instructions for every connection.
+    Constant(I),
+    /// Instructions listed by layer pair, then row pair, then block pair.
+    /// If a list is shorter than the number of pairs to connect,
+    /// the last instruction given is repeated for the remaining pairs.
+    /// If a list is empty, the default instructions are used.
+    Nested(Vec<Vec<Vec<I>>>),
+    /// Generate instructions from the coordinates of the two blocks being connected
+    /// and the index of the pair within the alignment.
+    Generator(Box<dyn Fn(Coord, Coord, usize) -> I + 'i>),
+}
+
+impl<'i, I: Clone + Default> Instructions<'i, I> {
+
+    /// Use the same instructions for every connection.
+    /// ```
+    /// use blok::connect::Instructions;
+    ///
+    /// let instructions = Instructions::constant(1u8);
+    /// assert_eq!(instructions.get((0, 0, 0), (0, 0, 0), (0, 1, 2), 7), 1);
+    /// ```
+    pub fn constant(instructions: I) -> Self {
+        Self::Constant(instructions)
+    }
+
+    /// Use instructions listed by layer pair, row pair, and block pair.
+    /// ```
+    /// use blok::connect::Instructions;
+    ///
+    /// let instructions = Instructions::nested(vec![vec![vec![1u8, 2]]]);
+    /// assert_eq!(instructions.get((0, 0, 1), (0, 0, 1), (0, 0, 1), 1), 2);
+    /// // The last instruction given is repeated:
+    /// assert_eq!(instructions.get((1, 1, 1), (1, 1, 1), (1, 1, 1), 5), 2);
+    /// ```
+    pub fn nested(instructions: Vec<Vec<Vec<I>>>) -> Self {
+        Self::Nested(instructions)
+    }
+
+    /// Generate instructions for each pair with a closure,
+    /// taking the coordinates of both blocks and the pair index.
+    /// ```
+    /// use blok::connect::Instructions;
+    ///
+    /// let instructions = Instructions::generate(|a, b, _| (a.2 + b.2) as u8);
+    /// assert_eq!(instructions.get((0, 0, 0), (0, 0, 1), (0, 0, 2), 0), 3);
+    /// ```
+    pub fn generate<F: Fn(Coord, Coord, usize) -> I + 'i>(generator: F) -> Self {
+        Self::Generator(Box::new(generator))
+    }
+
+    /// Get the instructions for a single pair.
+    /// `step` is the position of the pair within the layer, row, and block alignments,
+    /// `a` and `b` are the coordinates of the blocks being connected,
+    /// and `pair` is the index of the pair within the alignment,
+    /// counting pairs that are aligned but left unconnected, such as by a policy.
+    pub fn get(
+        &self,
+        step: Coord,
+        a: Coord,
+        b: Coord,
+        pair: usize
+    ) -> I {
+
+        match self {
+            Self::Constant(instructions) => instructions.clone(),
+            Self::Nested(layers) => {
+                // Fall back on the last entry when a list runs short.
+                layers.get(step.0).or(layers.last())
+                    .and_then(|rows| rows.get(step.1).or(rows.last()))
+                    .and_then(|blocks| blocks.get(step.2).or(blocks.last()))
+                    .cloned()
+                    .unwrap_or_default()
+            },
+            Self::Generator(generator) => generator(a, b, pair),
+        }
+    }
+
+}
+
+impl<I: Clone + Default> Default for Instructions<'_, I> {
+    fn default() -> Self {
+        Self::Constant(I::default())
+    }
+}
+
+impl<I> From<Vec<I>> for Instructions<'_, I> {
+    fn from(blocks: Vec<I>) -> Self {
+        Self::Nested(vec![vec![blocks]])
+    }
+}
+
+impl<I> From<Vec<Vec<I>>> for Instructions<'_, I> {
+    fn from(rows: Vec<Vec<I>>) -> Self {
+        Self::Nested(vec![rows])
+    }
+}
+
+impl<I> From<Vec<Vec<Vec<I>>>> for Instructions<'_, I> {
+    fn from(layers: Vec<Vec<Vec<I>>>) -> Self {
+        Self::Nested(layers)
+    }
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+
+    /// Test that nested instructions repeat the last entry at each level.
+    #[test] fn nested_instructions_test() {
+        let instructions: Instructions<u8> = vec![vec![vec![1, 2], vec![3]], vec![]].into();
+        assert_eq!(instructions.get((0, 0, 0), (0, 0, 0), (0, 0, 0), 0), 1);
+        assert_eq!(instructions.get((0, 0, 4), (0, 0, 0), (0, 0, 0), 0), 2);
+        assert_eq!(instructions.get((0, 3, 4), (0, 0, 0), (0, 0, 0), 0), 3);
+        // An empty list falls back on the default.
+        assert_eq!(instructions.get((1, 0, 0), (0, 0, 0), (0, 0, 0), 0), 0);
+        assert_eq!(instructions.get((2, 0, 0), (0, 0, 0), (0, 0, 0), 0), 0);
+
+        let empty: Instructions<u8> = Vec::<u8>::new().into();
+        assert_eq!(empty.get((0, 0, 0), (0, 0, 0), (0, 0, 0), 0), 0);
+    }
+
+    /// Test that generated instructions receive coordinates and pair index.
+    #[test] fn generated_instructions_test() {
+        let instructions = Instructions::generate(|a: Coord, b: Coord, pair| {
+            a.0 + b.1 + pair
+        });
+        assert_eq!(instructions.get((0, 0, 0), (1, 0, 0), (0, 2, 0), 3), 6);
+    }
+
+}
+
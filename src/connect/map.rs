@@ -0,0 +1,219 @@
+
+use std::collections::{ BTreeMap, BTreeSet };
+
+use serde::{ Serialize, Deserialize };
+
+use crate::Coord;
+use super::{ ConnectionPlan, PlannedConnection };
+
+/// Records connections as coordinate pairs, each carrying its instructions,
+/// so that the network can be kept beside a stack instead of inside its blocks.
+/// Serialized as a list of connections.
+/// ```
+/// use blok::connect::ConnectionMap;
+///
+/// let mut map = ConnectionMap::new();
+/// map.connect((0, 0, 0), (1, 0, 0), 0.5)
+///     .connect((0, 0, 1), (1, 0, 0), 0.25);
+///
+/// assert_eq!(map.len(), 2);
+/// assert_eq!(map.incoming((1, 0, 0)).count(), 2);
+/// assert_eq!(map.disconnect((0, 0, 0), (1, 0, 0)), Some(0.5));
+/// assert_eq!(map.outgoing((0, 0, 0)).count(), 0);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(from = "ConnectionPlan<I>", into = "ConnectionPlan<I>")]
+#[serde(bound(
+    serialize = "I: Clone + Serialize",
+    deserialize = "I: Clone + Deserialize<'de>"
+))]
+pub struct ConnectionMap<I> {
+    outgoing: BTreeMap<Coord, BTreeMap<Coord, I>>,
+    incoming: BTreeMap<Coord, BTreeSet<Coord>>,
+}
+
+impl<I> Default for ConnectionMap<I> {
+    fn default() -> Self {
+        Self { outgoing: BTreeMap::new(), incoming: BTreeMap::new() }
+    }
+}
+
+impl<I> ConnectionMap<I> {
+
+    /// Create an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a connection between two blocks.
+    /// Replaces the instructions if the blocks are already connected.
+    pub fn connect(&mut self, from: Coord, to: Coord, instructions: I) -> &mut Self {
+        self.outgoing.entry(from).or_default().insert(to, instructions);
+        self.incoming.entry(to).or_default().insert(from);
+        self
+    }
+
+    /// Remove the connection between two blocks, returning its instructions.
+    pub fn disconnect(&mut self, from: Coord, to: Coord) -> Option<I> {
+        let targets = self.outgoing.get_mut(&from)?;
+        let instructions = targets.remove(&to)?;
+        if targets.is_empty() { self.outgoing.remove(&from); }
+
+        if let Some(sources) = self.incoming.get_mut(&to) {
+            sources.remove(&from);
+            if sources.is_empty() { self.incoming.remove(&to); }
+        }
+
+        Some(instructions)
+    }
+
+    /// Remove every connection to or from a block.
+    pub fn disconnect_all(&mut self, coord: Coord) -> &mut Self {
+        let targets: Vec<Coord> = self.outgoing(coord).map(|(to, _)| to).collect();
+        let sources: Vec<Coord> = self.incoming(coord).map(|(from, _)| from).collect();
+        targets.into_iter().for_each(|to| { self.disconnect(coord, to); });
+        sources.into_iter().for_each(|from| { self.disconnect(from, coord); });
+        self
+    }
+
+    /// Get the instructions for the connection between two blocks, if there is one.
+    pub fn get(&self, from: Coord, to: Coord) -> Option<&I> {
+        self.outgoing.get(&from)?.get(&to)
+    }
+
+    /// Get mutable instructions for the connection between two blocks, if there is one.
+    pub fn get_mut(&mut self, from: Coord, to: Coord) -> Option<&mut I> {
+        self.outgoing.get_mut(&from)?.get_mut(&to)
+    }
+
+    /// Check whether one block is connected to another.
+    pub fn contains(&self, from: Coord, to: Coord) -> bool {
+        self.get(from, to).is_some()
+    }
+
+    /// Iterate over the blocks connected from a block, with the instructions for each.
+    pub fn outgoing(&self, coord: Coord) -> impl Iterator<Item = (Coord, &I)> + '_ {
+        self.outgoing.get(&coord)
+            .into_iter()
+            .flat_map(|targets| targets.iter().map(|(to, i)| (*to, i)))
+    }
+
+    /// Iterate over the blocks connected to a block, with the instructions for each.
+    pub fn incoming(&self, coord: Coord) -> impl Iterator<Item = (Coord, &I)> + '_ {
+        self.incoming.get(&coord)
+            .into_iter()
+            .flat_map(move |sources| sources.iter().map(move |from| {
+                (*from, &self.outgoing[from][&coord])
+            }))
+    }
+
+    /// Iterate over every connection as (from, to, instructions), ordered by coordinates.
+    pub fn edges(&self) -> impl Iterator<Item = (Coord, Coord, &I)> + '_ {
+        self.outgoing.iter()
+            .flat_map(|(from, targets)| targets.iter().map(|(to, i)| (*from, *to, i)))
+    }
+
+    /// Get the coordinates of every block with at least one connection.
+    pub fn coords(&self) -> BTreeSet<Coord> {
+        self.outgoing.keys()
+            .chain(self.incoming.keys())
+            .copied()
+            .collect()
+    }
+
+    /// Number of connections recorded.
+    pub fn len(&self) -> usize {
+        self.outgoing.values().map(|targets| targets.len()).sum()
+    }
+
+    /// Check whether any connections are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.outgoing.is_empty()
+    }
+
+}
+
+impl<I: Clone> ConnectionMap<I> {
+
+    /// Record every connection in a plan.
+    pub fn record(&mut self, plan: &ConnectionPlan<I>) -> &mut Self {
+        for connection in plan.iter() {
+            self.connect(connection.from, connection.to, connection.instructions.clone());
+        }
+        self
+    }
+
+    /// List the connections as a plan, ordered by coordinates.
+    pub fn to_plan(&self) -> ConnectionPlan<I> {
+        self.edges()
+            .map(|(from, to, i)| PlannedConnection { from, to, instructions: i.clone() })
+            .collect()
+    }
+
+}
+
+impl<I> FromIterator<PlannedConnection<I>> for ConnectionMap<I> {
+    fn from_iter<T: IntoIterator<Item = PlannedConnection<I>>>(iter: T) -> Self {
+        let mut map = Self::new();
+        for connection in iter {
+            map.connect(connection.from, connection.to, connection.instructions);
+        }
+        map
+    }
+}
+
+impl<I> From<ConnectionPlan<I>> for ConnectionMap<I> {
+    fn from(plan: ConnectionPlan<I>) -> Self {
+        plan.into_iter().collect()
+    }
+}
+
+impl<I: Clone> From<ConnectionMap<I>> for ConnectionPlan<I> {
+    fn from(map: ConnectionMap<I>) -> Self {
+        map.to_plan()
+    }
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+
+    /// Test connecting, querying and disconnecting.
+    #[test] fn map_connect_test() {
+        let mut map = ConnectionMap::new();
+        map.connect((0, 0, 0), (0, 0, 1), 1u8)
+            .connect((0, 0, 0), (0, 1, 0), 2)
+            .connect((0, 1, 0), (0, 0, 1), 3)
+            .connect((0, 0, 0), (0, 0, 1), 4);
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get((0, 0, 0), (0, 0, 1)), Some(&4));
+        assert_eq!(map.outgoing((0, 0, 0)).collect::<Vec<_>>(), vec![((0, 0, 1), &4), ((0, 1, 0), &2)]);
+        assert_eq!(map.incoming((0, 0, 1)).collect::<Vec<_>>(), vec![((0, 0, 0), &4), ((0, 1, 0), &3)]);
+
+        map.disconnect_all((0, 1, 0));
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.coords().len(), 2);
+        assert!(map.incoming((0, 1, 0)).next().is_none());
+    }
+
+    /// Test conversion to and from plans.
+    #[test] fn map_plan_test() {
+        let mut plan = ConnectionPlan::new();
+        plan.add((1, 0, 0), (0, 0, 0), 'a')
+            .add((0, 0, 0), (1, 0, 0), 'b');
+
+        let map = ConnectionMap::from(plan.clone());
+        assert_eq!(map.len(), 2);
+
+        // Plans made from maps are ordered by coordinates.
+        let mut sorted = plan.clone();
+        sorted.sort_by_key(|c| (c.from, c.to));
+        assert_eq!(map.to_plan(), sorted);
+    }
+
+}
+
@@ -1,180 +1,672 @@
 
-use crate::{ Node, Row, Layer, Stack, Aligner };
-
-// TODO Split into modules?
-
-/// Connect two row refs of blocks according to the parameters given.
-/// If the number of instructions is fewer than connections to perform,
-/// it will repeat the last instruction given for the remaining connections.
-pub fn row_connection<'c, N: Node>(
-    row1: &mut Vec<&'c mut N>, 
-    row2: &mut Vec<&'c mut N>, 
-    block_align: Aligner<&'c mut N>, 
-    mut instructions: Vec<N::ConnectionInstructions>
-) {
+use crate::{ Node, Row, Layer, Stack, Aligner, Coord };
+use crate::node::Address;
+
+/// Sources of instructions for procedural connection.
+pub mod instructions;
+pub use instructions::Instructions;
+
+/// Summaries of connections made.
+pub mod report;
+pub use report::ConnectionReport;
+
+/// Connections planned ahead of time.
+pub mod plan;
+pub use plan::{ ConnectionPlan, PlannedConnection, stack_plan };
+
+/// Connection graphs kept beside a stack.
+pub mod map;
+pub use map::ConnectionMap;
+
+/// Traversal of connection graphs.
+pub mod graph;
+
+/// Fluent construction of stack connections.
+pub mod builder;
+pub use builder::StackConnector;
+
+/// Alignment of stack structures.
+pub(crate) mod cascade;
+use cascade::{ cascade, cascade_layers, cascade_rows };
+
+/// Treatment of void blocks when connecting.
+pub mod policy;
+pub use policy::{ ConnectionPolicy, ConnectionOptions };
+
+/// Connect two row refs of blocks according to the parameters given,
+/// and report the connections made.
+/// Instructions are taken once for each aligned pair of blocks.
+/// Blocks are addressed by their position in the row refs, as (0, 0, b),
+/// with the first row as stack 0 and the second as stack 1.
+/// Nested instructions repeat the last instruction given when they run short,
+/// see `Instructions` for other ways to provide them.
+pub fn row_connection<'c, 'i, N: Node>(
+    row1: &mut Vec<&'c mut N>,
+    row2: &mut Vec<&'c mut N>,
+    block_align: Aligner<&'c mut N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+) -> ConnectionReport {
+
+    row_connection_with(row1, row2, block_align, instructions, ConnectionOptions::default())
+}
 
-    let alignment = block_align(row1, row2);
+/// Version of row_connection that treats void blocks according to the options given,
+/// and addresses blocks with the stack identifiers in the options.
+/// A `ConnectionPolicy` may be given in place of the options.
+pub fn row_connection_with<'c, 'i, N: Node>(
+    row1: &mut Vec<&'c mut N>,
+    row2: &mut Vec<&'c mut N>,
+    block_align: Aligner<&'c mut N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+    options: impl Into<ConnectionOptions>
+) -> ConnectionReport {
+
+    let options = options.into();
+    let keep = |block: &&'c mut N| !block.is_void();
+    let compaction = options.compact_voids.then_some((&keep as _, &keep as _));
+
+    let mut pairs = Vec::new();
+    let skipped = cascade_rows(row1, row2, block_align, compaction, |step, from, to| {
+        pairs.push((step, from, to))
+    });
+
+    let instructions = instructions.into();
+    let mut report = ConnectionReport::new();
+    report.skipped_out_of_range = skipped;
+    for (index, (step, from, to)) in pairs.into_iter().enumerate() {
+        let (a, b) = (&mut *row1[from.2], &mut *row2[to.2]);
+        connect_pair(a, b, (index, step, from, to), &instructions, &options, &mut report, None);
+    }
 
-    let last_instr = if let Some(instr) = instructions.last() {
-        instr.clone()
-    } else {
-        N::ConnectionInstructions::default()
-    };
+    report
+}
 
-    while alignment.len() > instructions.len() {
-        instructions.push(last_instr.clone())
-    }
+/// Connect a single aligned pair if the policy allows it,
+/// recording the result in the report, and in the map if one is given.
+/// The pair is given as (index, step, from, to), where index is its position in the alignment,
+/// counting pairs the policy skips.
+/// Blocks are addressed with the stack identifiers in the options.
+fn connect_pair<N: Node>(
+    a: &mut N,
+    b: &mut N,
+    pair: (usize, Coord, Coord, Coord),
+    instructions: &Instructions<N::ConnectionInstructions>,
+    options: &ConnectionOptions,
+    report: &mut ConnectionReport,
+    map: Option<&mut ConnectionMap<N::ConnectionInstructions>>
+) {
 
-    let mut step = 0usize;
-    for pair in alignment.iter() {
-        if row1.len() > pair.0 && row2.len() > pair.1 {
-            let block1 = &mut row1[pair.0];
-            let block2 = &mut row2[pair.1];
-            block1.connect(block2, &instructions[step])
-        } 
-        step += 1;
+    let (index, step, from, to) = pair;
+    let (a_id, b_id) = options.stack_ids;
+    if options.policy.allows(a, b) {
+        let instructions = instructions.get(step, from, to, index);
+        a.connect_at(Address::new(a_id, from), b, Address::new(b_id, to), &instructions);
+        report.record(from, to);
+        if let Some(map) = map {
+            map.connect(from, to, instructions);
+        }
+    } else {
+        report.skipped_by_policy += 1;
     }
 }
 
 impl<N: Node> Row<N> {
 
     /// Method version of row_connection.
-    pub fn connect<'c>(
+    pub fn connect<'c, 'i>(
         &'c mut self,
         other: &'c mut Self,
-        block_align: Aligner<&'c mut N>, 
-        instructions: Vec<N::ConnectionInstructions>
-    ) {
+        block_align: Aligner<&'c mut N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+    ) -> ConnectionReport {
+
+        self.connect_with(other, block_align, instructions, ConnectionOptions::default())
+    }
+
+    /// Method version of row_connection_with.
+    pub fn connect_with<'c, 'i>(
+        &'c mut self,
+        other: &'c mut Self,
+        block_align: Aligner<&'c mut N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+        options: impl Into<ConnectionOptions>
+    ) -> ConnectionReport {
 
         let mut this = self.get_all_mut();
         let mut other = other.get_all_mut();
-        row_connection(&mut this, &mut other, block_align, instructions);
+        row_connection_with(&mut this, &mut other, block_align, instructions, options)
     }
 
 }
 
 
 /// Connect two layer refs using row_connection.
-/// If the number of instruction lists is fewer than the number of rows to be connected,
-/// it will repeat the last instruction given for the remaining connections.
-pub fn layer_connection<'c, N: Node>(
-    layer1: &mut Vec<Vec<&'c mut N>>, 
-    layer2: &mut Vec<Vec<&'c mut N>>, 
+/// Nested instructions are listed by row pair, then block pair,
+/// and repeat the last list given for the remaining rows.
+/// Blocks are addressed by their position in the layer refs, as (0, r, b),
+/// with the first layer as stack 0 and the second as stack 1.
+pub fn layer_connection<'c, 'i, N: Node>(
+    layer1: &mut Vec<Vec<&'c mut N>>,
+    layer2: &mut Vec<Vec<&'c mut N>>,
     row_align: Aligner<Vec<&'c mut N>>,
-    block_align: Aligner<&'c mut N>, 
-    mut instructions: Vec<Vec<N::ConnectionInstructions>>
-) {
-
-    let alignment = row_align(&layer1, &layer2);
-
-    let last_instr = if let Some(instr) = instructions.last() {
-        instr.clone()
-    } else {
-        vec![N::ConnectionInstructions::default()]
-    };
+    block_align: Aligner<&'c mut N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+) -> ConnectionReport {
+
+    layer_connection_with(
+        layer1,
+        layer2,
+        row_align,
+        block_align,
+        instructions,
+        ConnectionOptions::default()
+    )
+}
 
-    while alignment.len() > instructions.len() {
-        instructions.push(last_instr.clone())
+/// Version of layer_connection that treats void blocks according to the options given,
+/// and addresses blocks with the stack identifiers in the options.
+/// A `ConnectionPolicy` may be given in place of the options.
+pub fn layer_connection_with<'c, 'i, N: Node>(
+    layer1: &mut Vec<Vec<&'c mut N>>,
+    layer2: &mut Vec<Vec<&'c mut N>>,
+    row_align: Aligner<Vec<&'c mut N>>,
+    block_align: Aligner<&'c mut N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+    options: impl Into<ConnectionOptions>
+) -> ConnectionReport {
+
+    let options = options.into();
+    let keep = |block: &&'c mut N| !block.is_void();
+    let compaction = options.compact_voids.then_some((&keep as _, &keep as _));
+
+    let mut pairs = Vec::new();
+    let skipped = cascade_layers(layer1, layer2, row_align, block_align, compaction, |step, from, to| {
+        pairs.push((step, from, to))
+    });
+
+    let instructions = instructions.into();
+    let mut report = ConnectionReport::new();
+    report.skipped_out_of_range = skipped;
+    for (index, (step, from, to)) in pairs.into_iter().enumerate() {
+        let (a, b) = (&mut *layer1[from.1][from.2], &mut *layer2[to.1][to.2]);
+        connect_pair(a, b, (index, step, from, to), &instructions, &options, &mut report, None);
     }
 
-    let mut step = 0usize;
-    for pair in alignment.iter() {
-        if layer1.len() > pair.0 && layer2.len() > pair.1 {
-            let row1 = &mut layer1[pair.0];
-            let row2 = &mut layer2[pair.1];
-            row_connection(row1, row2, block_align, instructions[step].clone());
-        } 
-        step += 1;
-    }
+    report
 }
 
 impl<N: Node> Layer<N> {
 
     /// Method version of layer_connection.
-    pub fn connect<'c>(
-        &'c mut self, 
-        other: &'c mut Self, 
+    pub fn connect<'c, 'i>(
+        &'c mut self,
+        other: &'c mut Self,
+        row_align: Aligner<Vec<&'c mut N>>,
+        block_align: Aligner<&'c mut N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+    ) -> ConnectionReport {
+
+        self.connect_with(other, row_align, block_align, instructions, ConnectionOptions::default())
+    }
+
+    /// Method version of layer_connection_with.
+    pub fn connect_with<'c, 'i>(
+        &'c mut self,
+        other: &'c mut Self,
         row_align: Aligner<Vec<&'c mut N>>,
-        block_align: Aligner<&'c mut N>, 
-        instructions: Vec<Vec<N::ConnectionInstructions>>
-    ) {
+        block_align: Aligner<&'c mut N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+        options: impl Into<ConnectionOptions>
+    ) -> ConnectionReport {
 
         let mut this = self.get_all_mut();
         let mut other = other.get_all_mut();
 
-        layer_connection(
-            &mut this, 
-            &mut other, 
+        layer_connection_with(
+            &mut this,
+            &mut other,
             row_align,
-            block_align, 
-            instructions
-        );
+            block_align,
+            instructions,
+            options
+        )
     }
 
 }
 
 
 /// Connect two stack refs using layer_connection.
-/// If the number of instruction lists is fewer than the number of rows to be connected,
-/// it will repeat the last instruction given for the remaining connections.
-pub fn stack_connection<'c, N: Node>(
-    stack1: &mut Vec<Vec<Vec<&'c mut N>>>, 
-    stack2: &mut Vec<Vec<Vec<&'c mut N>>>, 
+/// Nested instructions are listed by layer pair, then row pair, then block pair,
+/// and repeat the last list given for the remaining layers.
+/// Blocks are addressed by their coordinates, with the first stack as 0 and the second as 1.
+pub fn stack_connection<'c, 'i, N: Node>(
+    stack1: &mut Vec<Vec<Vec<&'c mut N>>>,
+    stack2: &mut Vec<Vec<Vec<&'c mut N>>>,
     layer_align: Aligner<Vec<Vec<&'c mut N>>>,
     row_align: Aligner<Vec<&'c mut N>>,
-    block_align: Aligner<&'c mut N>, 
-    mut instructions: Vec<Vec<Vec<N::ConnectionInstructions>>>
-) {
+    block_align: Aligner<&'c mut N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+) -> ConnectionReport {
+
+    stack_connection_with(
+        stack1,
+        stack2,
+        layer_align,
+        row_align,
+        block_align,
+        instructions,
+        ConnectionOptions::default()
+    )
+}
 
-    let alignment = layer_align(&stack1, &stack2);
+/// Version of stack_connection that treats void blocks according to the options given,
+/// and addresses blocks with the stack identifiers in the options.
+/// A `ConnectionPolicy` may be given in place of the options.
+pub fn stack_connection_with<'c, 'i, N: Node>(
+    stack1: &mut Vec<Vec<Vec<&'c mut N>>>,
+    stack2: &mut Vec<Vec<Vec<&'c mut N>>>,
+    layer_align: Aligner<Vec<Vec<&'c mut N>>>,
+    row_align: Aligner<Vec<&'c mut N>>,
+    block_align: Aligner<&'c mut N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+    options: impl Into<ConnectionOptions>
+) -> ConnectionReport {
+
+    let options = options.into();
+    let aligners = (layer_align, row_align, block_align);
+    let (pairs, mut report) = stack_pairs(stack1, stack2, aligners, options.compact_voids);
+
+    let instructions = instructions.into();
+    for (index, (step, from, to)) in pairs.into_iter().enumerate() {
+        let a = &mut *stack1[from.0][from.1][from.2];
+        let b = &mut *stack2[to.0][to.1][to.2];
+        connect_pair(a, b, (index, step, from, to), &instructions, &options, &mut report, None);
+    }
 
-    let last_instr = if let Some(instr) = instructions.last() {
-        instr.clone()
-    } else {
-        vec![vec![N::ConnectionInstructions::default()]]
-    };
+    report
+}
 
-    while alignment.len() > instructions.len() {
-        instructions.push(last_instr.clone())
-    }
+/// Version of stack_connection that also records each connection made in the map,
+/// along with the instructions it was made with.
+pub fn stack_connection_recorded<'c, 'i, N: Node>(
+    stack1: &mut Vec<Vec<Vec<&'c mut N>>>,
+    stack2: &mut Vec<Vec<Vec<&'c mut N>>>,
+    layer_align: Aligner<Vec<Vec<&'c mut N>>>,
+    row_align: Aligner<Vec<&'c mut N>>,
+    block_align: Aligner<&'c mut N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+    map: &mut ConnectionMap<N::ConnectionInstructions>
+) -> ConnectionReport {
+
+    stack_connection_recorded_with(
+        stack1,
+        stack2,
+        layer_align,
+        row_align,
+        block_align,
+        instructions,
+        ConnectionOptions::default(),
+        map
+    )
+}
 
-    let mut step = 0usize;
-    for pair in alignment.iter() {
-        if stack1.len() > pair.0 && stack2.len() > pair.1 {
-            let layer1 = &mut stack1[pair.0];
-            let layer2 = &mut stack2[pair.1];
-            layer_connection(layer1, layer2, row_align, block_align, instructions[step].clone());
-        } 
-        step += 1;
+/// Version of stack_connection_with that also records each connection made in the map.
+/// Pairs left unconnected by the options are left out of the map.
+#[allow(clippy::too_many_arguments)]
+pub fn stack_connection_recorded_with<'c, 'i, N: Node>(
+    stack1: &mut Vec<Vec<Vec<&'c mut N>>>,
+    stack2: &mut Vec<Vec<Vec<&'c mut N>>>,
+    layer_align: Aligner<Vec<Vec<&'c mut N>>>,
+    row_align: Aligner<Vec<&'c mut N>>,
+    block_align: Aligner<&'c mut N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+    options: impl Into<ConnectionOptions>,
+    map: &mut ConnectionMap<N::ConnectionInstructions>
+) -> ConnectionReport {
+
+    let options = options.into();
+    let aligners = (layer_align, row_align, block_align);
+    let (pairs, mut report) = stack_pairs(stack1, stack2, aligners, options.compact_voids);
+
+    let instructions = instructions.into();
+    for (index, (step, from, to)) in pairs.into_iter().enumerate() {
+        let a = &mut *stack1[from.0][from.1][from.2];
+        let b = &mut *stack2[to.0][to.1][to.2];
+        connect_pair(a, b, (index, step, from, to), &instructions, &options, &mut report, Some(&mut *map));
     }
+
+    report
+}
+
+/// Aligners for the layers, rows, and blocks of mutable stack refs.
+type MutAligners<'c, N> = (Aligner<Vec<Vec<&'c mut N>>>, Aligner<Vec<&'c mut N>>, Aligner<&'c mut N>);
+
+/// Find the aligned pairs between two stack refs, as (step, from, to),
+/// with a report counting the blocks left without a partner.
+fn stack_pairs<'c, N: Node>(
+    stack1: &mut Vec<Vec<Vec<&'c mut N>>>,
+    stack2: &mut Vec<Vec<Vec<&'c mut N>>>,
+    aligners: MutAligners<'c, N>,
+    compact_voids: bool
+) -> (Vec<(Coord, Coord, Coord)>, ConnectionReport) {
+
+    let keep = |block: &&'c mut N| !block.is_void();
+    let compaction = compact_voids.then_some((&keep as _, &keep as _));
+
+    let (layer_align, row_align, block_align) = aligners;
+    let mut pairs = Vec::new();
+    let mut report = ConnectionReport::new();
+    report.skipped_out_of_range = cascade(stack1, stack2, layer_align, row_align, block_align, compaction, |step, from, to| {
+        pairs.push((step, from, to))
+    });
+
+    (pairs, report)
 }
 
 impl<N: Node> Stack<N> {
 
     /// Method version of stack_connection.
-    pub fn connect<'c>(
-        &'c mut self, 
-        other: &'c mut Self, 
+    pub fn connect<'c, 'i>(
+        &'c mut self,
+        other: &'c mut Self,
+        layer_align: Aligner<Vec<Vec<&'c mut N>>>,
+        row_align: Aligner<Vec<&'c mut N>>,
+        block_align: Aligner<&'c mut N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+    ) -> ConnectionReport {
+
+        self.connect_with(
+            other,
+            layer_align,
+            row_align,
+            block_align,
+            instructions,
+            ConnectionOptions::default()
+        )
+    }
+
+    /// Method version of stack_connection_recorded.
+    pub fn connect_recorded<'c, 'i>(
+        &'c mut self,
+        other: &'c mut Self,
         layer_align: Aligner<Vec<Vec<&'c mut N>>>,
         row_align: Aligner<Vec<&'c mut N>>,
-        block_align: Aligner<&'c mut N>, 
-        instructions: Vec<Vec<Vec<N::ConnectionInstructions>>>
-    ) {
+        block_align: Aligner<&'c mut N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+        map: &mut ConnectionMap<N::ConnectionInstructions>
+    ) -> ConnectionReport {
 
         let mut this = self.get_all_mut();
         let mut other = other.get_all_mut();
 
-        stack_connection(
-            &mut this, 
-            &mut other, 
+        stack_connection_recorded(
+            &mut this,
+            &mut other,
             layer_align,
             row_align,
-            block_align, 
-            instructions
-        );
+            block_align,
+            instructions,
+            map
+        )
+    }
+
+    /// Method version of stack_connection_recorded_with.
+    #[allow(clippy::too_many_arguments)]
+    pub fn connect_recorded_with<'c, 'i>(
+        &'c mut self,
+        other: &'c mut Self,
+        layer_align: Aligner<Vec<Vec<&'c mut N>>>,
+        row_align: Aligner<Vec<&'c mut N>>,
+        block_align: Aligner<&'c mut N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+        options: impl Into<ConnectionOptions>,
+        map: &mut ConnectionMap<N::ConnectionInstructions>
+    ) -> ConnectionReport {
+
+        let mut this = self.get_all_mut();
+        let mut other = other.get_all_mut();
+
+        stack_connection_recorded_with(
+            &mut this,
+            &mut other,
+            layer_align,
+            row_align,
+            block_align,
+            instructions,
+            options,
+            map
+        )
+    }
+
+    /// Method version of stack_connection_with.
+    pub fn connect_with<'c, 'i>(
+        &'c mut self,
+        other: &'c mut Self,
+        layer_align: Aligner<Vec<Vec<&'c mut N>>>,
+        row_align: Aligner<Vec<&'c mut N>>,
+        block_align: Aligner<&'c mut N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+        options: impl Into<ConnectionOptions>
+    ) -> ConnectionReport {
+
+        let mut this = self.get_all_mut();
+        let mut other = other.get_all_mut();
+
+        stack_connection_with(
+            &mut this,
+            &mut other,
+            layer_align,
+            row_align,
+            block_align,
+            instructions,
+            options
+        )
     }
 
 }
 
 
 
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::{ Block, Row, Layer, Layout, layout, Alignment };
+    use crate::node::Address;
+    use crate::node::test::AddressBlock;
+    use crate::block::test::TestBlock;
+    use crate::types::stack::test::test_cube;
+    use super::{ Instructions, ConnectionPolicy, ConnectionOptions, ConnectionMap };
+
+
+    /// Test connecting with constant and generated instructions.
+    #[test] fn connect_instructions_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        cube1.connect(
+            &mut cube2,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Instructions::constant(2)
+        );
+        assert!(cube1.blocks().iter().all(|b| b.connections.len() == 2));
+
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        cube1.connect(
+            &mut cube2,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            // Connect once for blocks in the first layer, twice for the second.
+            Instructions::generate(|a, _, _| a.0 as u8 + 1)
+        );
+        let counts: Vec<usize> = cube1.blocks().iter()
+            .map(|b| b.connections.len())
+            .collect();
+        assert_eq!(counts, vec![1, 1, 1, 1, 2, 2, 2, 2]);
+
+        // Pair index counts the aligned pairs in order.
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        cube1.connect(
+            &mut cube2,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Instructions::generate(|_, _, pair| (pair % 2) as u8)
+        );
+        let counts: Vec<usize> = cube1.blocks().iter()
+            .map(|b| b.connections.len())
+            .collect();
+        assert_eq!(counts, vec![0, 1, 0, 1, 0, 1, 0, 1]);
+
+        // Pairs skipped by the policy keep their place in the alignment.
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        cube1.blocks_mut()[0] = TestBlock::void();
+        let report = cube1.connect_with(
+            &mut cube2,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Instructions::generate(|_, _, pair| (pair == 1) as u8),
+            ConnectionPolicy::SkipVoids
+        );
+        assert_eq!(report.skipped_by_policy, 1);
+        assert_eq!(cube1.blocks()[1].connections, vec!["1".to_string()]);
+        assert!(cube1.blocks()[2].connections.is_empty());
+    }
+
+    /// Test that nested instructions still repeat the last entry.
+    #[test] fn connect_nested_instructions_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        cube1.connect(
+            &mut cube2,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            vec![vec![vec![1, 2]], vec![vec![3]]]
+        );
+        let counts: Vec<usize> = cube1.blocks().iter()
+            .map(|b| b.connections.len())
+            .collect();
+        assert_eq!(counts, vec![1, 2, 1, 2, 3, 3, 3, 3]);
+    }
+
+    /// Test that void blocks are treated according to the connection policy.
+    #[test] fn connect_policy_test() {
+        let new_row = || {
+            let blocks = ["", "a", "b"].iter()
+                .map(|id| TestBlock::create(&id.to_string()))
+                .collect();
+            Row::wrap(blocks)
+        };
+
+        // The void is aligned with "a", and "a" with "b".
+        let (mut row1, mut row2) = (new_row(), new_row());
+        row2.remove(0);
+        row1.connect(&mut row2, Alignment::corresponding, vec![1]);
+        assert_eq!(row1[0].connections, vec!["a".to_string()]);
+
+        let (mut row1, mut row2) = (new_row(), new_row());
+        row2.remove(0);
+        row1.connect_with(&mut row2, Alignment::corresponding, vec![1], ConnectionPolicy::SkipVoids);
+        assert!(row1[0].connections.is_empty());
+        assert_eq!(row1[1].connections, vec!["b".to_string()]);
+
+        // Voids are left out of the alignment, so "a" aligns with "a".
+        let (mut row1, mut row2) = (new_row(), new_row());
+        row2.remove(0);
+        let options = ConnectionOptions::default().compact_voids();
+        row1.connect_with(&mut row2, Alignment::corresponding, vec![1], options);
+        assert!(row1[0].connections.is_empty());
+        assert_eq!(row1[1].connections, vec!["a".to_string()]);
+        assert_eq!(row1[2].connections, vec!["b".to_string()]);
+        assert_eq!(row1.len(), 3);
+    }
+
+    /// Test recording a stack connection in a map, and saving the map.
+    #[test] fn connect_recorded_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        let mut map = ConnectionMap::new();
+        let report = cube1.connect_recorded(
+            &mut cube2,
+            Alignment::reversed,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Instructions::generate(|_, from, _| from.2 as u8 + 1),
+            &mut map
+        );
+
+        assert_eq!(report.total, 8);
+        assert_eq!(map.len(), 8);
+        assert_eq!(map.get((0, 1, 1), (1, 1, 1)), Some(&2));
+        assert_eq!(map.outgoing((1, 0, 0)).collect::<Vec<_>>(), vec![((0, 0, 0), &1)]);
+
+        let saved = serde_json::to_string(&map).expect("Map serializes");
+        let loaded: ConnectionMap<u8> = serde_json::from_str(&saved).expect("Map deserializes");
+        assert_eq!(loaded, map);
+    }
+
+    /// Test that a recorded connection follows the options given.
+    #[test] fn connect_recorded_with_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        cube1.blocks_mut()[1].to_void();
+        let mut map = ConnectionMap::new();
+        let report = cube1.connect_recorded_with(
+            &mut cube2,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            vec![1],
+            ConnectionPolicy::SkipVoids,
+            &mut map
+        );
+
+        assert_eq!(report.total, 7);
+        assert_eq!(report.skipped_by_policy, 1);
+        assert_eq!(map.len(), 7);
+        assert_eq!(map.get((0, 0, 1), (0, 0, 1)), None);
+        assert_eq!(map.get((0, 0, 0), (0, 0, 0)), Some(&1));
+    }
+
+    /// Test the report returned from a stack connection.
+    #[test] fn connect_report_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        cube2.add_layer(cube2.clone_layer(0).expect("Layer exists"));
+
+        let report = cube1.connect(
+            &mut cube2,
+            Alignment::dense,
+            Alignment::corresponding,
+            Alignment::dense,
+            vec![1]
+        );
+
+        // Two layers, each aligned with three layers of two rows with four pairs each.
+        assert_eq!(report.total, 48);
+        assert_eq!(report.per_layer[&0], 24);
+        assert_eq!(report.per_row[&(1, 1)], 12);
+        assert_eq!(report.skipped_out_of_range, 0);
+        assert_eq!(report.fan_out_histogram()[&6], 8);
+        assert_eq!(report.fan_in_histogram()[&4], 12);
+    }
+
+    /// Test that rows and layers address blocks with the stack ids from the options.
+    #[test] fn connect_address_test() {
+        let options = || ConnectionOptions::default().stack_ids(2, 3);
+
+        let mut row1 = Row::wrap(vec![AddressBlock::default(); 2]);
+        let mut row2 = row1.clone();
+        row1.connect_with(&mut row2, Alignment::reversed, vec![()], options());
+        assert_eq!(row1[0].links, vec![
+            (Address::new(2, (0, 0, 0)), Address::new(3, (0, 0, 1)))
+        ]);
+
+        let mut layer1 = Layer::<AddressBlock>::new();
+        layer1.populate(layout![1, 2], &());
+        let mut layer2 = layer1.clone();
+        layer1.connect_with(&mut layer2, Alignment::corresponding, Alignment::reversed, vec![()], options());
+        assert_eq!(layer1.blocks()[1].links, vec![
+            (Address::new(2, (0, 1, 0)), Address::new(3, (0, 1, 1)))
+        ]);
+    }
+
+}
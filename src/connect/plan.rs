@@ -0,0 +1,563 @@
+
+use derive_more::{ Deref, DerefMut };
+use serde::{ Serialize, Deserialize };
+
+use crate::{ Block, Node, Stack, Aligner, Coord };
+use crate::node::Address;
+use crate::order::{ Topology, Offset };
+use crate::order::coord::flat_coords;
+use super::{ Instructions, ConnectionReport, ConnectionPolicy, ConnectionOptions };
+use super::cascade::{ cascade, index_structure };
+
+/// A single connection to be made, from the block at one coordinate to the block at another.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PlannedConnection<I> {
+    /// Coordinates of the block being connected.
+    pub from: Coord,
+    /// Coordinates of the block it connects to.
+    pub to: Coord,
+    /// Instructions passed to `Node::connect`.
+    pub instructions: I,
+}
+
+/// An ordered list of connections that can be inspected, saved, and executed later.
+/// Produced by running alignments without connecting any blocks.
+#[derive(Debug, Default, Clone, Deref, DerefMut, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionPlan<I>(Vec<PlannedConnection<I>>);
+
+impl<I: Clone> ConnectionPlan<I> {
+
+    /// Create an empty plan.
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Create a plan from an existing vector of planned connections.
+    pub fn wrap(vec: Vec<PlannedConnection<I>>) -> Self {
+        Self(vec)
+    }
+
+    /// Add a connection to the end of the plan.
+    pub fn add(&mut self, from: Coord, to: Coord, instructions: I) -> &mut Self {
+        self.push(PlannedConnection { from, to, instructions });
+        self
+    }
+
+    /// Create a new plan containing only the connections for which the predicate is true.
+    pub fn filter<F: Fn(&PlannedConnection<I>) -> bool>(&self, predicate: F) -> Self {
+        let filtered = self.iter()
+            .filter(|c| predicate(c))
+            .cloned()
+            .collect();
+
+        Self(filtered)
+    }
+
+    /// Append the connections from another plan after those in this plan.
+    pub fn merge(&mut self, mut other: Self) -> &mut Self {
+        self.0.append(&mut other.0);
+        self
+    }
+
+    /// Create a new plan containing the connections in this plan
+    /// that do not appear in the other.
+    pub fn difference(&self, other: &Self) -> Self
+    where I: PartialEq {
+        self.filter(|c| !other.contains(c))
+    }
+
+    /// Make the planned connections from blocks in the source stack to blocks in the target.
+    /// Connections whose coordinates cannot be found are skipped.
+    /// Blocks are addressed with the source stack as 0 and the target as 1.
+    pub fn execute<N: Node<ConnectionInstructions = I>>(
+        &self,
+        source: &mut Stack<N>,
+        target: &mut Stack<N>
+    ) -> ConnectionReport {
+        self.execute_as(source, target, (0, 1))
+    }
+
+    /// Make the planned connections from blocks in the source stack to blocks in the target,
+    /// addressing blocks with the given (source, target) stack identifiers.
+    pub fn execute_as<N: Node<ConnectionInstructions = I>>(
+        &self,
+        source: &mut Stack<N>,
+        target: &mut Stack<N>,
+        (source_id, target_id): (usize, usize)
+    ) -> ConnectionReport {
+
+        let source_indexes = index_structure(source.layouts());
+        let target_indexes = index_structure(target.layouts());
+        let source = source.blocks_mut();
+        let target = target.blocks_mut();
+
+        let mut report = ConnectionReport::new();
+        for connection in self.iter() {
+            let i = find_index(&source_indexes, connection.from);
+            let j = find_index(&target_indexes, connection.to);
+            if let (Some(i), Some(j)) = (i, j) {
+                source[i].connect_at(
+                    Address::new(source_id, connection.from),
+                    &mut target[j],
+                    Address::new(target_id, connection.to),
+                    &connection.instructions
+                );
+                report.record(connection.from, connection.to);
+            } else {
+                report.skipped_out_of_range += 1;
+            }
+        }
+
+        report
+    }
+
+    /// Make the planned connections between blocks of the same stack.
+    /// Connections whose coordinates cannot be found,
+    /// or that would connect a block to itself, are skipped.
+    /// Blocks are addressed with the stack as 0.
+    pub fn execute_within<N: Node<ConnectionInstructions = I>>(
+        &self,
+        stack: &mut Stack<N>
+    ) -> ConnectionReport {
+        self.execute_within_as(stack, 0)
+    }
+
+    /// Make the planned connections between blocks of the same stack,
+    /// addressing blocks with the given stack identifier.
+    pub fn execute_within_as<N: Node<ConnectionInstructions = I>>(
+        &self,
+        stack: &mut Stack<N>,
+        id: usize
+    ) -> ConnectionReport {
+
+        let indexes = index_structure(stack.layouts());
+        let blocks = stack.blocks_mut();
+
+        let mut report = ConnectionReport::new();
+        for connection in self.iter() {
+            let i = find_index(&indexes, connection.from);
+            let j = find_index(&indexes, connection.to);
+            let (Some(i), Some(j)) = (i, j) else {
+                report.skipped_out_of_range += 1;
+                continue
+            };
+
+            if let Some((a, b)) = pair_mut(blocks, i, j) {
+                a.connect_at(
+                    Address::new(id, connection.from),
+                    b,
+                    Address::new(id, connection.to),
+                    &connection.instructions
+                );
+                report.record(connection.from, connection.to);
+            } else {
+                report.skipped_self += 1;
+            }
+        }
+
+        report
+    }
+
+}
+
+impl<I> FromIterator<PlannedConnection<I>> for ConnectionPlan<I> {
+    fn from_iter<T: IntoIterator<Item = PlannedConnection<I>>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<I> IntoIterator for ConnectionPlan<I> {
+    type Item = PlannedConnection<I>;
+    type IntoIter = std::vec::IntoIter<PlannedConnection<I>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Look up the flat index of a block from its coordinates.
+pub(crate) fn find_index(indexes: &[Vec<Vec<usize>>], coord: Coord) -> Option<usize> {
+    indexes.get(coord.0)?
+        .get(coord.1)?
+        .get(coord.2)
+        .copied()
+}
+
+/// Get mutable references to two different blocks in the same collection.
+/// Returns None if the indexes are the same.
+pub(crate) fn pair_mut<B: Block>(blocks: &mut [B], i: usize, j: usize) -> Option<(&mut B, &mut B)> {
+    if i < j {
+        let (head, tail) = blocks.split_at_mut(j);
+        Some((&mut head[i], &mut tail[0]))
+    } else if i > j {
+        let (head, tail) = blocks.split_at_mut(i);
+        Some((&mut tail[0], &mut head[j]))
+    } else {
+        None
+    }
+}
+
+/// Plan a connection between two stack refs, using the same alignments as stack_connection,
+/// without connecting any blocks.
+pub fn stack_plan<'c, 'i, N: Node>(
+    stack1: &[Vec<Vec<&'c N>>],
+    stack2: &[Vec<Vec<&'c N>>],
+    layer_align: Aligner<Vec<Vec<&'c N>>>,
+    row_align: Aligner<Vec<&'c N>>,
+    block_align: Aligner<&'c N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+) -> ConnectionPlan<N::ConnectionInstructions> {
+
+    stack_plan_with(
+        stack1,
+        stack2,
+        layer_align,
+        row_align,
+        block_align,
+        instructions,
+        ConnectionOptions::default()
+    )
+}
+
+/// Version of stack_plan that treats void blocks according to the options given,
+/// matching stack_connection_with.
+pub fn stack_plan_with<'c, 'i, N: Node>(
+    stack1: &[Vec<Vec<&'c N>>],
+    stack2: &[Vec<Vec<&'c N>>],
+    layer_align: Aligner<Vec<Vec<&'c N>>>,
+    row_align: Aligner<Vec<&'c N>>,
+    block_align: Aligner<&'c N>,
+    instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+    options: impl Into<ConnectionOptions>
+) -> ConnectionPlan<N::ConnectionInstructions> {
+
+    let aligners = (layer_align, row_align, block_align);
+    plan_refs(stack1, stack2, aligners, instructions.into(), options.into(), false)
+}
+
+/// Aligners for the layers, rows, and blocks of stack refs.
+type RefAligners<'c, N> = (Aligner<Vec<Vec<&'c N>>>, Aligner<Vec<&'c N>>, Aligner<&'c N>);
+
+/// Plan a connection between two stack refs.
+/// When planning within a stack, pairs that align a block with itself are left out
+/// of the alignment, so that they are not given a pair index.
+fn plan_refs<'c, N: Node>(
+    stack1: &[Vec<Vec<&'c N>>],
+    stack2: &[Vec<Vec<&'c N>>],
+    aligners: RefAligners<'c, N>,
+    instructions: Instructions<N::ConnectionInstructions>,
+    options: ConnectionOptions,
+    within: bool
+) -> ConnectionPlan<N::ConnectionInstructions> {
+
+    let keep = |block: &&'c N| !block.is_void();
+    let compaction = options.compact_voids.then_some((&keep as _, &keep as _));
+
+    let (layer_align, row_align, block_align) = aligners;
+    let (mut refs1, mut refs2) = (stack1.to_vec(), stack2.to_vec());
+    let mut plan = ConnectionPlan::new();
+    let mut index = 0usize;
+    cascade(&mut refs1, &mut refs2, layer_align, row_align, block_align, compaction, |step, from, to| {
+        if within && from == to {
+            return
+        }
+
+        let a = stack1[from.0][from.1][from.2];
+        let b = stack2[to.0][to.1][to.2];
+        if options.policy.allows(a, b) {
+            let instructions = instructions.get(step, from, to, index);
+            plan.add(from, to, instructions);
+        }
+        index += 1;
+    });
+
+    plan
+}
+
+impl<N: Node> Stack<N> {
+
+    /// Plan a connection from this stack to another, without connecting any blocks.
+    /// Method version of stack_plan.
+    /// ```
+    /// use blok::{ Stack, Alignment, Layout, layout };
+    /// # use blok::{ Block, Node };
+    /// # #[derive(Default, Clone)]
+    /// # struct MyNode { connections: usize }
+    /// # impl Block for MyNode {
+    /// #     type CreationInstructions = ();
+    /// #     fn create(_: &()) -> Self { MyNode { connections: 0 } }
+    /// #     fn void() -> Self { Self::default() }
+    /// #     fn is_void(&self) -> bool { false }
+    /// # }
+    /// # impl Node for MyNode {
+    /// #     type ConnectionInstructions = u8;
+    /// #     fn connect(&mut self, _: &mut Self, _: &u8) { self.connections += 1 }
+    /// # }
+    ///
+    /// let mut stack1 = Stack::<MyNode>::new();
+    /// stack1.populate(vec![layout![2]], &());
+    /// let mut stack2 = stack1.clone();
+    ///
+    /// let plan = stack1.plan_connection(
+    ///     &stack2,
+    ///     Alignment::corresponding,
+    ///     Alignment::corresponding,
+    ///     Alignment::reversed,
+    ///     vec![5]
+    /// );
+    ///
+    /// assert_eq!(plan.len(), 2);
+    /// assert_eq!(plan[0].from, (0, 0, 0));
+    /// assert_eq!(plan[0].to, (0, 0, 1));
+    /// assert_eq!(plan[0].instructions, 5);
+    ///
+    /// let report = plan.execute(&mut stack1, &mut stack2);
+    /// assert_eq!(report.total, 2);
+    /// ```
+    pub fn plan_connection<'c, 'i>(
+        &'c self,
+        other: &'c Self,
+        layer_align: Aligner<Vec<Vec<&'c N>>>,
+        row_align: Aligner<Vec<&'c N>>,
+        block_align: Aligner<&'c N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+    ) -> ConnectionPlan<N::ConnectionInstructions> {
+
+        let this = self.get_all_ref();
+        let other = other.get_all_ref();
+        stack_plan(&this, &other, layer_align, row_align, block_align, instructions)
+    }
+
+    /// Plan a connection between the blocks of this stack, without connecting any blocks.
+    /// Pairs that would connect a block to itself are left out of the plan.
+    pub fn plan_within<'c, 'i>(
+        &'c self,
+        layer_align: Aligner<Vec<Vec<&'c N>>>,
+        row_align: Aligner<Vec<&'c N>>,
+        block_align: Aligner<&'c N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>
+    ) -> ConnectionPlan<N::ConnectionInstructions> {
+
+        self.plan_within_with(layer_align, row_align, block_align, instructions, ConnectionOptions::default())
+    }
+
+    /// Version of plan_within that treats void blocks according to the options given.
+    /// A `ConnectionPolicy` may be given in place of the options.
+    pub fn plan_within_with<'c, 'i>(
+        &'c self,
+        layer_align: Aligner<Vec<Vec<&'c N>>>,
+        row_align: Aligner<Vec<&'c N>>,
+        block_align: Aligner<&'c N>,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+        options: impl Into<ConnectionOptions>
+    ) -> ConnectionPlan<N::ConnectionInstructions> {
+
+        let this = self.get_all_ref();
+        let aligners = (layer_align, row_align, block_align);
+        plan_refs(&this, &this, aligners, instructions.into(), options.into(), true)
+    }
+
+    /// Plan connections from each block of this stack to the blocks at the given offsets from it,
+    /// finding positions past the ends of each axis by the topology.
+    /// Each block is connected once to each distinct neighbor, and never to itself.
+    /// Instructions are given the step (0, 0, i), where i is the index of the offset,
+    /// so a list of instructions can be given with one entry per offset.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// use blok::connect::ConnectionPolicy;
+    /// use blok::order::{ Topology, FACE_OFFSETS };
+    /// # use blok::{ Block, Node };
+    /// # #[derive(Default, Clone)]
+    /// # struct MyNode { connections: usize }
+    /// # impl Block for MyNode {
+    /// #     type CreationInstructions = ();
+    /// #     fn create(_: &()) -> Self { MyNode { connections: 0 } }
+    /// #     fn void() -> Self { Self::default() }
+    /// #     fn is_void(&self) -> bool { false }
+    /// # }
+    /// # impl Node for MyNode {
+    /// #     type ConnectionInstructions = u8;
+    /// #     fn connect(&mut self, _: &mut Self, _: &u8) { self.connections += 1 }
+    /// # }
+    ///
+    /// let mut stack = Stack::<MyNode>::new();
+    /// stack.populate(vec![layout![4; 4]], &());
+    ///
+    /// // On a torus, every block of the layer has four neighbors.
+    /// let plan = stack.plan_spatial(&FACE_OFFSETS, &Topology::toroidal(), vec![1], ConnectionPolicy::ConnectAll);
+    /// assert_eq!(plan.len(), 16 * 4);
+    ///
+    /// let plan = stack.plan_spatial(&FACE_OFFSETS, &Topology::bounded(), vec![1], ConnectionPolicy::ConnectAll);
+    /// // Each of the 24 edges between neighbors is planned in both directions.
+    /// assert_eq!(plan.len(), 2 * 24);
+    ///
+    /// plan.execute_within(&mut stack);
+    /// assert_eq!(stack.get_block_ref(0, 0, 0).unwrap().connections, 2);
+    /// ```
+    pub fn plan_spatial<'i>(
+        &self,
+        offsets: &[Offset],
+        topology: &Topology,
+        instructions: impl Into<Instructions<'i, N::ConnectionInstructions>>,
+        policy: ConnectionPolicy
+    ) -> ConnectionPlan<N::ConnectionInstructions> {
+
+        let instructions = instructions.into();
+        let indexes = index_structure(self.layouts());
+        let mut plan = ConnectionPlan::new();
+        let mut index = 0usize;
+        for (from, block) in flat_coords(self.layouts()).into_iter().zip(self.blocks()) {
+            let mut seen = Vec::new();
+            for (i, &offset) in offsets.iter().enumerate() {
+                let Some(to) = topology.resolve(self.layouts(), from, offset) else { continue };
+                if to == from || seen.contains(&to) {
+                    continue
+                }
+                seen.push(to);
+
+                let target = &self.blocks()[find_index(&indexes, to)
+                    .expect("Resolved coordinates are present")];
+                if policy.allows(block, target) {
+                    let instructions = instructions.get((0, 0, i), from, to, index);
+                    plan.add(from, to, instructions);
+                }
+                index += 1;
+            }
+        }
+
+        plan
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+    use crate::Alignment;
+    use crate::types::stack::test::test_cube;
+
+
+    /// Test that a plan matches the connections made by stack_connection.
+    #[test] fn plan_matches_connection_test() {
+        let (mut cube1, mut cube2) = (test_cube(), test_cube());
+        let plan = cube1.plan_connection(
+            &cube2,
+            Alignment::reversed,
+            Alignment::corresponding,
+            Alignment::dense,
+            Instructions::generate(|a, _, _| a.2 as u8 + 1)
+        );
+        assert_eq!(plan.len(), 16);
+        assert!(cube1.blocks().iter().all(|b| b.connections.is_empty()));
+
+        let report = plan.execute(&mut cube1, &mut cube2);
+        assert_eq!(report.total, 16);
+
+        let (mut cube3, mut cube4) = (test_cube(), test_cube());
+        cube3.connect(
+            &mut cube4,
+            Alignment::reversed,
+            Alignment::corresponding,
+            Alignment::dense,
+            Instructions::generate(|a, _, _| a.2 as u8 + 1)
+        );
+        assert_eq!(cube1.blocks(), cube3.blocks());
+    }
+
+    /// Test filtering, merging, and diffing plans.
+    #[test] fn plan_filter_merge_test() {
+        let cube = test_cube();
+        let plan = cube.plan_within(
+            Alignment::dense,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            vec![1]
+        );
+        // Self-pairs are left out.
+        assert_eq!(plan.len(), 8);
+
+        let upward = plan.filter(|c| c.to.0 > c.from.0);
+        let downward = plan.difference(&upward);
+        assert_eq!(upward.len(), 4);
+        assert_eq!(downward.len(), 4);
+
+        let mut merged = upward.clone();
+        merged.merge(downward);
+        assert_eq!(merged.len(), 8);
+
+        let mut cube = test_cube();
+        let report = upward.execute_within(&mut cube);
+        assert_eq!(report.total, 4);
+        assert_eq!(report.per_layer[&0], 4);
+        assert_eq!(report.fan_in[&(1, 0, 0)], 1);
+        assert_eq!(cube.blocks()[0].connections, vec!["4".to_string()]);
+        assert!(cube.blocks()[4].connections.is_empty());
+    }
+
+    /// Test that planning within a stack agrees with connecting within it,
+    /// including the pair indexes given to generated instructions.
+    #[test] fn plan_within_matches_connect_within_test() {
+        let generate = || Instructions::generate(|_, _, pair| pair as u8 + 1);
+        let plan = test_cube().plan_within(
+            Alignment::dense,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            generate()
+        );
+
+        let mut cube = test_cube();
+        let built = cube.connect_within()
+            .layers(Alignment::dense)
+            .instructions(generate())
+            .plan();
+        assert_eq!(plan, built);
+        assert_eq!(plan.last().map(|c| c.instructions), Some(8));
+
+        let mut planned = test_cube();
+        plan.execute_within(&mut planned);
+        cube.connect_within()
+            .layers(Alignment::dense)
+            .instructions(generate())
+            .run();
+        assert_eq!(planned.blocks(), cube.blocks());
+    }
+
+    /// Test that plans within a stack take the same options as connections within it.
+    #[test] fn plan_within_with_options_test() {
+        let mut cube = test_cube();
+        cube.blocks_mut()[4].to_void();
+
+        let plan = cube.plan_within_with(
+            Alignment::dense,
+            Alignment::corresponding,
+            Alignment::corresponding,
+            Instructions::generate(|_, _, pair| pair as u8),
+            ConnectionPolicy::SkipVoids
+        );
+        let built = cube.connect_within()
+            .layers(Alignment::dense)
+            .instructions(Instructions::generate(|_, _, pair| pair as u8))
+            .skip_voids()
+            .plan();
+
+        assert_eq!(plan, built);
+        assert_eq!(plan.len(), 6);
+        assert!(plan.iter().all(|c| c.from != (1, 0, 0) && c.to != (1, 0, 0)));
+    }
+
+    /// Test that connections outside of the stack are skipped.
+    #[test] fn plan_out_of_range_test() {
+        let mut plan = ConnectionPlan::new();
+        plan.add((0, 0, 0), (0, 0, 1), 1)
+            .add((0, 0, 0), (5, 0, 0), 1);
+
+        let mut cube = test_cube();
+        let report = plan.execute_within(&mut cube);
+        assert_eq!(report.total, 1);
+        assert_eq!(report.skipped_out_of_range, 1);
+    }
+
+}
+
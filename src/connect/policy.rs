@@ -0,0 +1,99 @@
+
+use serde::{ Serialize, Deserialize };
+
+use crate::Block;
+
+/// Decides how void blocks are treated when making connections.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ConnectionPolicy {
+    /// Connect every aligned pair, including voids.
+    #[default]
+    ConnectAll,
+    /// Skip pairs where either block is void.
+    SkipVoids,
+    /// Skip pairs where the block being connected is void.
+    SkipVoidSource,
+    /// Skip pairs where the block being connected to is void.
+    SkipVoidTarget,
+}
+
+impl ConnectionPolicy {
+
+    /// Check whether a pair of blocks may be connected under this policy.
+    /// ```
+    /// use blok::connect::ConnectionPolicy;
+    ///
+    /// assert!(ConnectionPolicy::SkipVoidTarget.allows(&1u8, &2u8));
+    /// assert!(!ConnectionPolicy::SkipVoidTarget.allows(&1u8, &0u8));
+    /// assert!(ConnectionPolicy::SkipVoidSource.allows(&1u8, &0u8));
+    /// ```
+    pub fn allows<B: Block>(&self, from: &B, to: &B) -> bool {
+        match self {
+            Self::ConnectAll => true,
+            Self::SkipVoids => !from.is_void() && !to.is_void(),
+            Self::SkipVoidSource => !from.is_void(),
+            Self::SkipVoidTarget => !to.is_void(),
+        }
+    }
+
+}
+
+/// Settings for the connection functions that take them, such as `stack_connection_with`.
+/// The default connects every aligned pair, with voids included in the alignment,
+/// and addresses blocks with the first structure as 0 and the second as 1.
+/// ```
+/// use blok::connect::{ ConnectionOptions, ConnectionPolicy };
+///
+/// let options = ConnectionOptions::new(ConnectionPolicy::SkipVoidSource)
+///     .compact_voids()
+///     .stack_ids(4, 2);
+/// assert!(options.compact_voids);
+/// assert_eq!(options.stack_ids, (4, 2));
+/// assert_eq!(ConnectionOptions::default().policy, ConnectionPolicy::ConnectAll);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionOptions {
+    /// Decides which aligned pairs are connected.
+    pub policy: ConnectionPolicy,
+    /// Align blocks as though voids were removed from each row,
+    /// so that indices passed to the block aligner count non-void blocks only.
+    /// Coordinates given to instructions still refer to positions in the full rows.
+    pub compact_voids: bool,
+    /// Identifiers passed to `Node::connect_at` for the (first, second) structures.
+    pub stack_ids: (usize, usize),
+}
+
+impl ConnectionOptions {
+
+    /// Create options with the given policy, without compacting voids,
+    /// and with the default stack identifiers.
+    pub fn new(policy: ConnectionPolicy) -> Self {
+        Self { policy, compact_voids: false, stack_ids: (0, 1) }
+    }
+
+    /// Align blocks over the non-void blocks of each row only.
+    pub fn compact_voids(mut self) -> Self {
+        self.compact_voids = true;
+        self
+    }
+
+    /// Set the identifiers passed to `Node::connect_at` for the first and second structures.
+    pub fn stack_ids(mut self, first: usize, second: usize) -> Self {
+        self.stack_ids = (first, second);
+        self
+    }
+
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self::new(ConnectionPolicy::default())
+    }
+}
+
+impl From<ConnectionPolicy> for ConnectionOptions {
+    fn from(policy: ConnectionPolicy) -> Self {
+        Self::new(policy)
+    }
+}
+
@@ -0,0 +1,133 @@
+
+use std::collections::BTreeMap;
+
+use crate::Coord;
+
+/// Summarizes the connections made by a procedural connection.
+/// Counts are attributed to the block being connected ("from"),
+/// except for fan-in, which counts connections to each block ("to").
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ConnectionReport {
+    /// Total number of connections made.
+    pub total: usize,
+    /// Connections made from each layer, by layer index.
+    pub per_layer: BTreeMap<usize, usize>,
+    /// Connections made from each row, by (layer, row) index.
+    pub per_row: BTreeMap<(usize, usize), usize>,
+    /// Aligned pairs skipped because an index fell outside of the structure.
+    pub skipped_out_of_range: usize,
+    /// Aligned pairs skipped by the connection policy or by a filter.
+    pub skipped_by_policy: usize,
+    /// Pairs skipped because they would connect a block to itself.
+    pub skipped_self: usize,
+    /// Number of connections made from each block.
+    pub fan_out: BTreeMap<Coord, usize>,
+    /// Number of connections made to each block.
+    pub fan_in: BTreeMap<Coord, usize>,
+}
+
+impl ConnectionReport {
+
+    /// Create an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a connection made between two blocks.
+    pub fn record(&mut self, from: Coord, to: Coord) -> &mut Self {
+        self.total += 1;
+        *self.per_layer.entry(from.0).or_default() += 1;
+        *self.per_row.entry((from.0, from.1)).or_default() += 1;
+        *self.fan_out.entry(from).or_default() += 1;
+        *self.fan_in.entry(to).or_default() += 1;
+        self
+    }
+
+    /// Combine the counts from another report into this one.
+    pub fn merge(&mut self, other: &Self) -> &mut Self {
+        self.total += other.total;
+        self.skipped_out_of_range += other.skipped_out_of_range;
+        self.skipped_by_policy += other.skipped_by_policy;
+        self.skipped_self += other.skipped_self;
+        merge_helper(&mut self.per_layer, &other.per_layer);
+        merge_helper(&mut self.per_row, &other.per_row);
+        merge_helper(&mut self.fan_out, &other.fan_out);
+        merge_helper(&mut self.fan_in, &other.fan_in);
+        self
+    }
+
+    /// Count the blocks with each number of outgoing connections.
+    /// Blocks that made no connections are not counted.
+    /// ```
+    /// use blok::connect::ConnectionReport;
+    ///
+    /// let mut report = ConnectionReport::new();
+    /// report.record((0, 0, 0), (0, 0, 1))
+    ///     .record((0, 0, 0), (0, 0, 2))
+    ///     .record((0, 0, 1), (0, 0, 2));
+    ///
+    /// // One block connected once, and one connected twice.
+    /// assert_eq!(report.fan_out_histogram().into_iter().collect::<Vec<_>>(), vec![(1, 1), (2, 1)]);
+    /// ```
+    pub fn fan_out_histogram(&self) -> BTreeMap<usize, usize> {
+        histogram_helper(&self.fan_out)
+    }
+
+    /// Count the blocks with each number of incoming connections.
+    /// Blocks that received no connections are not counted.
+    pub fn fan_in_histogram(&self) -> BTreeMap<usize, usize> {
+        histogram_helper(&self.fan_in)
+    }
+
+}
+
+/// Add the counts in one map to those in another.
+fn merge_helper<K: Ord + Clone>(counts: &mut BTreeMap<K, usize>, other: &BTreeMap<K, usize>) {
+    for (key, count) in other.iter() {
+        *counts.entry(key.clone()).or_default() += count;
+    }
+}
+
+/// Count how many keys share each degree.
+fn histogram_helper(degrees: &BTreeMap<Coord, usize>) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+    for degree in degrees.values() {
+        *histogram.entry(*degree).or_default() += 1;
+    }
+    histogram
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+
+    /// Test recording and merging reports.
+    #[test] fn report_merge_test() {
+        let mut report1 = ConnectionReport::new();
+        report1.record((0, 0, 0), (1, 0, 0))
+            .record((0, 1, 0), (1, 0, 0));
+        report1.skipped_out_of_range = 2;
+
+        let mut report2 = ConnectionReport::new();
+        report2.record((1, 0, 0), (0, 0, 0));
+        report2.skipped_by_policy = 1;
+        report2.skipped_self = 2;
+
+        report1.merge(&report2);
+        assert_eq!(report1.total, 3);
+        assert_eq!(report1.per_layer[&0], 2);
+        assert_eq!(report1.per_layer[&1], 1);
+        assert_eq!(report1.per_row[&(0, 1)], 1);
+        assert_eq!(report1.fan_in[&(1, 0, 0)], 2);
+        assert_eq!(report1.skipped_out_of_range, 2);
+        assert_eq!(report1.skipped_by_policy, 1);
+        assert_eq!(report1.skipped_self, 2);
+        assert_eq!(report1.fan_in_histogram()[&1], 1);
+        assert_eq!(report1.fan_in_histogram()[&2], 1);
+    }
+
+}
+
@@ -0,0 +1,248 @@
+
+use std::fmt::Write;
+
+use crate::{ Block, Stack, Coord };
+use crate::connect::ConnectionMap;
+
+/// Labels a block from its contents and coordinates.
+type Labeler<'e, B> = Box<dyn Fn(&B, Coord) -> String + 'e>;
+
+/// Writes the blocks and connections of a stack as a Graphviz DOT digraph,
+/// with one cluster per layer and one subgraph per row.
+/// Each block is positioned by its coordinates, with rows stacked downward
+/// and layers placed below one another.
+/// Connections to coordinates outside of the stack are left out.
+/// ```
+/// use blok::{ Stack, Layout, layout };
+/// use blok::connect::ConnectionMap;
+/// use blok::export::DotExport;
+/// # use blok::Block;
+/// # #[derive(Default, Clone)]
+/// # struct MyBlock;
+/// # impl Block for MyBlock {
+/// #     type CreationInstructions = ();
+/// #     fn create(_: &()) -> Self { MyBlock }
+/// #     fn void() -> Self { MyBlock }
+/// #     fn is_void(&self) -> bool { false }
+/// # }
+///
+/// let mut stack = Stack::<MyBlock>::new();
+/// stack.populate(vec![layout![1]; 2], &());
+///
+/// let mut map = ConnectionMap::new();
+/// map.connect((0, 0, 0), (1, 0, 0), ());
+///
+/// let dot = DotExport::new(&stack)
+///     .map(&map)
+///     .label(|_, (l, _, _)| format!("L{}", l))
+///     .render();
+///
+/// assert!(dot.contains("\"0_0_0\" -> \"1_0_0\";"));
+/// assert!(dot.contains("label=\"L1\""));
+/// ```
+pub struct DotExport<'e, B: Block> {
+    stack: &'e Stack<B>,
+    edges: Vec<(Coord, Coord)>,
+    label: Option<Labeler<'e, B>>,
+    hide_voids: bool,
+}
+
+impl<'e, B: Block> DotExport<'e, B> {
+
+    /// Start an export of the given stack, with no connections.
+    pub fn new(stack: &'e Stack<B>) -> Self {
+        Self {
+            stack,
+            edges: Vec::new(),
+            label: None,
+            hide_voids: false,
+        }
+    }
+
+    /// Draw the connections recorded in a map.
+    pub fn map<I>(mut self, map: &ConnectionMap<I>) -> Self {
+        self.edges.extend(map.edges().map(|(from, to, _)| (from, to)));
+        self
+    }
+
+    /// Draw connections listed by each block,
+    /// given a function that returns the coordinates a block is connected to.
+    pub fn edges(mut self, neighbors: impl Fn(&B) -> Vec<Coord>) -> Self {
+        let map = self.stack.connection_map(neighbors);
+        self.edges.extend(map.edges().map(|(from, to, _)| (from, to)));
+        self
+    }
+
+    /// Label each block using a function of the block and its coordinates.
+    /// Blocks are labeled with their coordinates by default.
+    pub fn label<F: Fn(&B, Coord) -> String + 'e>(mut self, label: F) -> Self {
+        self.label = Some(Box::new(label));
+        self
+    }
+
+    /// Leave out void blocks, along with their connections.
+    pub fn hide_voids(mut self) -> Self {
+        self.hide_voids = true;
+        self
+    }
+
+    /// Write the DOT text.
+    pub fn render(&self) -> String {
+
+        let blocks = self.stack.get_all_ref();
+        let height = blocks.iter().map(|layer| layer.len()).max().unwrap_or(0) + 1;
+        let shown = |(l, r, b): Coord| {
+            blocks.get(l)
+                .and_then(|layer| layer.get(r))
+                .and_then(|row| row.get(b))
+                .is_some_and(|block| !(self.hide_voids && block.is_void()))
+        };
+
+        let mut dot = String::from("digraph stack {\n");
+        for (l, layer) in blocks.iter().enumerate() {
+            writeln!(dot, "    subgraph cluster_{} {{", l).unwrap();
+            writeln!(dot, "        label=\"layer {}\";", l).unwrap();
+
+            for (r, row) in layer.iter().enumerate() {
+                writeln!(dot, "        subgraph row_{}_{} {{", l, r).unwrap();
+                writeln!(dot, "            rank=same;").unwrap();
+
+                for (b, block) in row.iter().enumerate() {
+                    if !shown((l, r, b)) { continue }
+                    let coord = (l, r, b);
+                    let label = match &self.label {
+                        Some(label) => label(block, coord),
+                        None => format!("{:?}", coord),
+                    };
+                    writeln!(
+                        dot,
+                        "            {} [label=\"{}\", pos=\"{},-{}!\"];",
+                        node_id(coord),
+                        escape_helper(&label),
+                        b,
+                        l * height + r
+                    ).unwrap();
+                }
+
+                writeln!(dot, "        }}").unwrap();
+            }
+
+            writeln!(dot, "    }}").unwrap();
+        }
+
+        for &(from, to) in self.edges.iter() {
+            if shown(from) && shown(to) {
+                writeln!(dot, "    {} -> {};", node_id(from), node_id(to)).unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+}
+
+impl<B: Block> Stack<B> {
+
+    /// Write the stack and the connections in a map as a Graphviz DOT digraph.
+    /// See `DotExport` for more options.
+    pub fn to_dot<I>(&self, map: &ConnectionMap<I>) -> String {
+        DotExport::new(self).map(map).render()
+    }
+
+}
+
+/// Identify a block in DOT by its coordinates.
+fn node_id((l, r, b): Coord) -> String {
+    format!("\"{}_{}_{}\"", l, r, b)
+}
+
+/// Escape quotes and backslashes in a DOT string.
+fn escape_helper(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::Block;
+    use crate::connect::ConnectionMap;
+    use crate::types::stack::test::test_cube;
+    use super::DotExport;
+
+
+    /// Test the DOT output for a small stack.
+    #[test] fn dot_snapshot_test() {
+        let stack = test_cube();
+        let mut map = ConnectionMap::new();
+        map.connect((0, 1, 1), (1, 0, 0), ())
+            .connect((0, 0, 0), (5, 0, 0), ());
+
+        let dot = DotExport::new(&stack)
+            .map(&map)
+            .label(|block, _| format!("\"{}\"", block.id))
+            .render();
+
+        assert_eq!(dot, [
+            "digraph stack {",
+            "    subgraph cluster_0 {",
+            "        label=\"layer 0\";",
+            "        subgraph row_0_0 {",
+            "            rank=same;",
+            "            \"0_0_0\" [label=\"\\\"0\\\"\", pos=\"0,-0!\"];",
+            "            \"0_0_1\" [label=\"\\\"1\\\"\", pos=\"1,-0!\"];",
+            "        }",
+            "        subgraph row_0_1 {",
+            "            rank=same;",
+            "            \"0_1_0\" [label=\"\\\"2\\\"\", pos=\"0,-1!\"];",
+            "            \"0_1_1\" [label=\"\\\"3\\\"\", pos=\"1,-1!\"];",
+            "        }",
+            "    }",
+            "    subgraph cluster_1 {",
+            "        label=\"layer 1\";",
+            "        subgraph row_1_0 {",
+            "            rank=same;",
+            "            \"1_0_0\" [label=\"\\\"4\\\"\", pos=\"0,-3!\"];",
+            "            \"1_0_1\" [label=\"\\\"5\\\"\", pos=\"1,-3!\"];",
+            "        }",
+            "        subgraph row_1_1 {",
+            "            rank=same;",
+            "            \"1_1_0\" [label=\"\\\"6\\\"\", pos=\"0,-4!\"];",
+            "            \"1_1_1\" [label=\"\\\"7\\\"\", pos=\"1,-4!\"];",
+            "        }",
+            "    }",
+            "    \"0_1_1\" -> \"1_0_0\";",
+            "}",
+            "",
+        ].join("\n"));
+    }
+
+    /// Test that hidden voids are left out along with their connections.
+    #[test] fn dot_hide_voids_test() {
+        let mut stack = test_cube();
+        stack.get_block_mut(1, 0, 0).unwrap().to_void();
+
+        let map = stack.connection_map(|block| match block.id.as_str() {
+            "0" => vec![(0, 1, 0), (1, 0, 0)],
+            _ => Vec::new(),
+        });
+
+        let shown = DotExport::new(&stack).map(&map).render();
+        assert!(shown.contains("\"1_0_0\" [label=\"(1, 0, 0)\""));
+        assert!(shown.contains("\"0_0_0\" -> \"1_0_0\";"));
+
+        let hidden = DotExport::new(&stack)
+            .edges(|block| match block.id.as_str() {
+                "0" => vec![(0, 1, 0), (1, 0, 0)],
+                _ => Vec::new(),
+            })
+            .hide_voids()
+            .render();
+        assert!(!hidden.contains("\"1_0_0\""));
+        assert!(hidden.contains("\"0_0_0\" -> \"0_1_0\";"));
+    }
+
+}
+
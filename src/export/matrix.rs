@@ -0,0 +1,295 @@
+
+use crate::{ Block, Node, Layer, Stack, Coord };
+use crate::node::Address;
+use crate::connect::{ ConnectionMap, ConnectionReport };
+use crate::connect::plan::pair_mut;
+use crate::order::coord::flat_coords;
+
+/// Methods for converting connections between layers to and from weight matrices.
+/// Matrix rows follow the flat block order of the first layer,
+/// and matrix columns follow the flat block order of the second.
+impl<B: Block> Layer<B> {
+
+    /// Write the connections from this layer to another as a dense weight matrix,
+    /// using a function to extract a weight from the instructions of each connection.
+    /// Layers use coordinates (0, r, b), so "from" coordinates in the map refer to this layer
+    /// and "to" coordinates refer to the other. Unconnected pairs have a weight of 0.
+    /// ```
+    /// use blok::{ Layer, Layout, layout };
+    /// use blok::connect::ConnectionMap;
+    /// # use blok::Block;
+    /// # #[derive(Default, Clone)]
+    /// # struct MyBlock;
+    /// # impl Block for MyBlock {
+    /// #     type CreationInstructions = ();
+    /// #     fn create(_: &()) -> Self { MyBlock }
+    /// #     fn void() -> Self { MyBlock }
+    /// #     fn is_void(&self) -> bool { false }
+    /// # }
+    ///
+    /// let mut layer1 = Layer::<MyBlock>::new();
+    /// layer1.populate(layout![1, 1], &());
+    /// let mut layer2 = Layer::<MyBlock>::new();
+    /// layer2.populate(layout![3], &());
+    ///
+    /// let mut map = ConnectionMap::new();
+    /// map.connect((0, 1, 0), (0, 0, 2), 0.5);
+    ///
+    /// let matrix = layer1.weight_matrix(&layer2, &map, |w| *w);
+    /// assert_eq!(matrix, vec![vec![0.0, 0.0, 0.0], vec![0.0, 0.0, 0.5]]);
+    /// ```
+    pub fn weight_matrix<I>(
+        &self,
+        other: &Self,
+        map: &ConnectionMap<I>,
+        weight: impl Fn(&I) -> f64
+    ) -> Vec<Vec<f64>> {
+
+        let positions1 = flat_coords(std::slice::from_ref(self.layout()));
+        let positions2 = flat_coords(std::slice::from_ref(other.layout()));
+        matrix_helper(&positions1, &positions2, map, weight)
+    }
+
+}
+
+impl<B: Block> Stack<B> {
+
+    /// Write the connections from one layer of this stack to another as a dense weight matrix,
+    /// using a function to extract a weight from the instructions of each connection.
+    /// Matrix rows follow the flat block order of layer `l1`, and columns that of layer `l2`.
+    /// Unconnected pairs have a weight of 0.
+    /// Returns an error if either layer does not exist.
+    pub fn weight_matrix<I>(
+        &self,
+        l1: usize,
+        l2: usize,
+        map: &ConnectionMap<I>,
+        weight: impl Fn(&I) -> f64
+    ) -> anyhow::Result<Vec<Vec<f64>>> {
+
+        for l in [l1, l2] {
+            if l >= self.layouts().len() {
+                return Err(anyhow::anyhow!("Layer {} is not present in the stack", l))
+            }
+        }
+
+        let coords = flat_coords(self.layouts());
+        let positions1 = layer_coords_helper(&coords, l1);
+        let positions2 = layer_coords_helper(&coords, l2);
+        Ok(matrix_helper(&positions1, &positions2, map, weight))
+    }
+
+}
+
+impl<N: Node> Layer<N> {
+
+    /// Connect the blocks of this layer to those of another, as directed by a matrix.
+    /// Each entry is turned into instructions for the connection between
+    /// the block for its row and the block for its column, or None to leave them unconnected.
+    /// Blocks are addressed with this layer as 0 and the other as 1.
+    /// Returns an error if the matrix does not match the sizes of the layers.
+    /// ```
+    /// use blok::{ Layer, Layout, layout };
+    /// # use blok::{ Block, Node };
+    /// # #[derive(Default, Clone)]
+    /// # struct MyNode { weights: Vec<f64> }
+    /// # impl Block for MyNode {
+    /// #     type CreationInstructions = ();
+    /// #     fn create(_: &()) -> Self { Self::default() }
+    /// #     fn void() -> Self { Self::default() }
+    /// #     fn is_void(&self) -> bool { false }
+    /// # }
+    /// # impl Node for MyNode {
+    /// #     type ConnectionInstructions = f64;
+    /// #     fn connect(&mut self, _: &mut Self, w: &f64) { self.weights.push(*w) }
+    /// # }
+    ///
+    /// let mut layer1 = Layer::<MyNode>::new();
+    /// layer1.populate(layout![2], &());
+    /// let mut layer2 = layer1.clone();
+    ///
+    /// let matrix = vec![vec![0.0, 0.3], vec![0.7, 0.0]];
+    /// let report = layer1.connect_from_matrix(&mut layer2, &matrix, |w| (*w != 0.0).then_some(*w))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(report.total, 2);
+    /// assert_eq!(layer1.blocks()[1].weights, vec![0.7]);
+    /// ```
+    pub fn connect_from_matrix<T>(
+        &mut self,
+        other: &mut Self,
+        matrix: &[Vec<T>],
+        instructions: impl Fn(&T) -> Option<N::ConnectionInstructions>
+    ) -> anyhow::Result<ConnectionReport> {
+
+        let positions1 = flat_coords(std::slice::from_ref(self.layout()));
+        let positions2 = flat_coords(std::slice::from_ref(other.layout()));
+        check_helper(matrix, positions1.len(), positions2.len())?;
+
+        let (blocks1, blocks2) = (self.blocks_mut(), other.blocks_mut());
+        let mut report = ConnectionReport::new();
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, entry) in row.iter().enumerate() {
+                let Some(instructions) = instructions(entry) else { continue };
+                let (from, to) = (positions1[i], positions2[j]);
+                blocks1[i].connect_at(
+                    Address::new(0, from),
+                    &mut blocks2[j],
+                    Address::new(1, to),
+                    &instructions
+                );
+                report.record(from, to);
+            }
+        }
+
+        Ok(report)
+    }
+
+}
+
+impl<N: Node> Stack<N> {
+
+    /// Connect the blocks of one layer of this stack to those of another, as directed by a matrix.
+    /// Each entry is turned into instructions for the connection between
+    /// the block for its row and the block for its column, or None to leave them unconnected.
+    /// Entries that would connect a block to itself are skipped.
+    /// As with layers, blocks are addressed with layer `l1` as 0 and layer `l2` as 1,
+    /// using their coordinates within this stack.
+    /// Returns an error if either layer does not exist,
+    /// or if the matrix does not match the sizes of the layers.
+    pub fn connect_from_matrix<T>(
+        &mut self,
+        l1: usize,
+        l2: usize,
+        matrix: &[Vec<T>],
+        instructions: impl Fn(&T) -> Option<N::ConnectionInstructions>
+    ) -> anyhow::Result<ConnectionReport> {
+
+        let start1 = self.find_layer_start(l1)?.unwrap_or(0);
+        let start2 = self.find_layer_start(l2)?.unwrap_or(0);
+        let coords = flat_coords(self.layouts());
+        let positions1 = layer_coords_helper(&coords, l1);
+        let positions2 = layer_coords_helper(&coords, l2);
+        check_helper(matrix, positions1.len(), positions2.len())?;
+
+        let blocks = self.blocks_mut();
+        let mut report = ConnectionReport::new();
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, entry) in row.iter().enumerate() {
+                let Some(instructions) = instructions(entry) else { continue };
+                let (from, to) = (positions1[i], positions2[j]);
+                if let Some((a, b)) = pair_mut(blocks, start1 + i, start2 + j) {
+                    a.connect_at(Address::new(0, from), b, Address::new(1, to), &instructions);
+                    report.record(from, to);
+                } else {
+                    report.skipped_self += 1;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+}
+
+/// Pick out the coordinates of a single layer's blocks from those of the whole stack.
+fn layer_coords_helper(coords: &[Coord], l: usize) -> Vec<Coord> {
+    coords.iter()
+        .filter(|coord| coord.0 == l)
+        .copied()
+        .collect()
+}
+
+/// Fill a matrix with the weights of connections between two layers.
+fn matrix_helper<I>(
+    positions1: &[Coord],
+    positions2: &[Coord],
+    map: &ConnectionMap<I>,
+    weight: impl Fn(&I) -> f64
+) -> Vec<Vec<f64>> {
+
+    positions1.iter()
+        .map(|&from| {
+            positions2.iter()
+                .map(|&to| map.get(from, to).map_or(0.0, &weight))
+                .collect()
+        })
+        .collect()
+}
+
+/// Check that a matrix has a row for each source block and a column for each target block.
+fn check_helper<T>(matrix: &[Vec<T>], rows: usize, columns: usize) -> anyhow::Result<()> {
+    if matrix.len() != rows || matrix.iter().any(|row| row.len() != columns) {
+        return Err(anyhow::anyhow!(
+            "Matrix does not match the size of the layers ({} by {})", rows, columns
+        ))
+    }
+    Ok(())
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::{ Stack, Layer, Layout, layout };
+    use crate::block::test::TestBlock;
+    use crate::node::Address;
+    use crate::node::test::AddressBlock;
+    use crate::connect::ConnectionMap;
+
+    /// Test that weights round-trip through a stack.
+    #[test] fn matrix_round_trip_test() {
+        let mut stack = Stack::<TestBlock>::new();
+        stack.populate(vec![layout![1, 1], layout![2]], &"a".to_string());
+
+        let matrix = vec![vec![0u8, 2], vec![1, 0]];
+        let report = stack.connect_from_matrix(0, 1, &matrix, |n| (*n > 0).then_some(*n))
+            .unwrap();
+        assert_eq!(report.total, 2);
+        assert_eq!(stack.blocks()[0].connections.len(), 2);
+        assert_eq!(stack.blocks()[1].connections.len(), 1);
+
+        let mut map = ConnectionMap::new();
+        map.connect((0, 0, 0), (1, 0, 1), 2u8)
+            .connect((0, 1, 0), (1, 0, 0), 1)
+            .connect((1, 0, 0), (0, 0, 0), 9);
+        let weights = stack.weight_matrix(0, 1, &map, |n| *n as f64).unwrap();
+        assert_eq!(weights, vec![vec![0.0, 2.0], vec![1.0, 0.0]]);
+
+        assert!(stack.connect_from_matrix(0, 1, &[vec![0u8; 2]], |_| Some(1)).is_err());
+        assert!(stack.weight_matrix(0, 2, &map, |n| *n as f64).is_err());
+    }
+
+    /// Test that entries connecting a block to itself are skipped.
+    #[test] fn matrix_within_layer_test() {
+        let mut stack = Stack::<TestBlock>::new();
+        stack.populate(vec![layout![2]], &"a".to_string());
+
+        let report = stack.connect_from_matrix(0, 0, &[vec![1u8, 1], vec![1, 1]], |n| Some(*n))
+            .unwrap();
+        assert_eq!(report.total, 2);
+        assert_eq!(report.skipped_self, 2);
+        assert_eq!(report.skipped_by_policy, 0);
+    }
+
+    /// Test that layers and stacks both address the matrix rows as 0 and its columns as 1.
+    #[test] fn matrix_address_test() {
+        let mut layer1 = Layer::<AddressBlock>::new();
+        layer1.populate(layout![1], &());
+        let mut layer2 = layer1.clone();
+        layer1.connect_from_matrix(&mut layer2, &[vec![()]], |_| Some(())).unwrap();
+        assert_eq!(layer1.blocks()[0].links, vec![
+            (Address::new(0, (0, 0, 0)), Address::new(1, (0, 0, 0)))
+        ]);
+
+        let mut stack = Stack::<AddressBlock>::new();
+        stack.populate(vec![layout![1], layout![1]], &());
+        stack.connect_from_matrix(1, 0, &[vec![()]], |_| Some(())).unwrap();
+        assert_eq!(stack.blocks()[1].links, vec![
+            (Address::new(0, (1, 0, 0)), Address::new(1, (0, 0, 0)))
+        ]);
+    }
+
+}
+
@@ -0,0 +1,7 @@
+
+/// Graphviz DOT export of stack connectivity.
+pub mod dot;
+pub use dot::DotExport;
+
+/// Dense weight matrices of connections between layers.
+pub mod matrix;
@@ -3,16 +3,21 @@ pub mod block;
 pub use block::Block;
 
 pub mod types;
-pub use types::{ Row, Layer, Stack };
+pub use types::{ Row, Layer, Stack, StackView };
 
 pub mod order;
-pub use order::{ Layout, Alignment, Aligner };
+pub use order::{ Layout, Alignment, Aligner, Coord };
 
 pub mod node;
 pub use node::Node;
 
 pub mod connect;
 
+pub mod export;
+
+#[cfg(feature = "propagate")]
+pub mod propagate;
+
 pub mod primitive;
 
 
@@ -1,5 +1,28 @@
 
-use super::Block;
+use serde::{ Serialize, Deserialize };
+
+use super::{ Block, Coord };
+
+/// Locates a block during connection: 
+/// an identifier for the stack that holds it, and its (l, r, b) coordinates within that stack.
+/// Connection functions identify the first stack as 0 and the second as 1,
+/// unless other identifiers are given.
+/// Rows and layers are addressed like stacks of a single row or layer,
+/// so a block in a layer is at (0, r, b), and one in a row at (0, 0, b).
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct Address {
+    /// Identifies the stack holding the block.
+    pub stack: usize,
+    /// Position of the block within its stack.
+    pub coord: Coord,
+}
+
+impl Address {
+    /// Create an address from a stack identifier and block coordinates.
+    pub fn new(stack: usize, coord: Coord) -> Self {
+        Self { stack, coord }
+    }
+}
 
 pub trait Node: Block {
     
@@ -13,6 +36,45 @@ pub trait Node: Block {
         instructions: &Self::ConnectionInstructions
     ); 
 
+    /// Connect a parent node to a new child, given the address of each.
+    /// This is what the connection functions call, so override it 
+    /// when nodes need to know where they and their partners are.
+    /// By default, addresses are ignored and `connect` is used.
+    /// ```
+    /// use blok::{ Block, Node, node::Address };
+    ///
+    /// #[derive(Default, Clone)]
+    /// struct MyNode { partners: Vec<Address> }
+    ///
+    /// impl Block for MyNode {
+    ///     type CreationInstructions = ();
+    ///     fn create(_: &()) -> Self { Self::default() }
+    ///     fn void() -> Self { Self::default() }
+    ///     fn is_void(&self) -> bool { false }
+    /// }
+    ///
+    /// impl Node for MyNode {
+    ///     type ConnectionInstructions = ();
+    ///     fn connect(&mut self, _: &mut Self, _: &()) {}
+    ///     fn connect_at(&mut self, _: Address, _: &mut Self, other_at: Address, _: &()) {
+    ///         self.partners.push(other_at)
+    ///     }
+    /// }
+    ///
+    /// let (mut a, mut b) = (MyNode::default(), MyNode::default());
+    /// a.connect_at(Address::new(0, (0, 0, 0)), &mut b, Address::new(1, (2, 1, 0)), &());
+    /// assert_eq!(a.partners[0].coord, (2, 1, 0));
+    /// ```
+    fn connect_at(
+        &mut self,
+        _self_at: Address,
+        other: &mut Self,
+        _other_at: Address,
+        instructions: &Self::ConnectionInstructions
+    ) {
+        self.connect(other, instructions)
+    }
+
     // TODO: disconnect
 
 }
@@ -39,6 +101,25 @@ pub trait Node: Block {
         } 
     }
 
+    /// Block that records the addresses it is connected at.
+    #[derive(Default, Clone)]
+    pub(crate) struct AddressBlock { pub(crate) links: Vec<(Address, Address)> }
+
+    impl Block for AddressBlock {
+        type CreationInstructions = ();
+        fn create(_: &()) -> Self { Self::default() }
+        fn void() -> Self { Self::default() }
+        fn is_void(&self) -> bool { false }
+    }
+
+    impl Node for AddressBlock {
+        type ConnectionInstructions = ();
+        fn connect(&mut self, _: &mut Self, _: &()) {}
+        fn connect_at(&mut self, self_at: Address, _: &mut Self, other_at: Address, _: &()) {
+            self.links.push((self_at, other_at))
+        }
+    }
+
     /// Test for connecting nodes.
     #[test] fn node_connect_test() {
         let mut a = TestBlock::create(&"a".to_string());
@@ -47,4 +128,12 @@ pub trait Node: Block {
         assert_eq!(a.connections[0], "b".to_string());
     }
 
+    /// Test that connecting by address defaults to connect.
+    #[test] fn node_connect_at_test() {
+        let mut a = TestBlock::create(&"a".to_string());
+        let mut b = TestBlock::create(&"b".to_string());
+        a.connect_at(Address::new(0, (0, 0, 0)), &mut b, Address::new(1, (0, 0, 1)), &2);
+        assert_eq!(a.connections, vec!["b".to_string(), "b".to_string()]);
+    }
+
 }
@@ -1,6 +1,8 @@
 
 use derive_more::{ Deref, DerefMut };
 
+use super::Boundary;
+
 
 /// Associates two rows of blocks, layers of rows, or stacks of layer, by index.
 /// Used for scheduling procedural connection generation.
@@ -24,6 +26,8 @@ pub type Aligner<T> = fn(&Vec<T>, &Vec<T>) -> Alignment;
 
 /// Here are some basic Aligners. 
 /// You can also write your own, as needed.
+// Aligners take `&Vec` so that they coerce to the `Aligner` signature.
+#[allow(clippy::ptr_arg)]
 impl Alignment {
 
     /// Align corresponding indices.
@@ -70,7 +74,7 @@ impl Alignment {
         let mut vec = Vec::new();
 
         for i in 0..max { 
-            vec.push((0+i, r2-1-i)) 
+            vec.push((i, r2-1-i)) 
         }
 
         Self::wrap(vec)
@@ -189,6 +193,84 @@ impl Alignment {
         Self::wrap(vec)
     }
 
+    /// Align each index of the first row to the index a distance along in the second,
+    /// finding indices past the ends of the second row by the boundary given.
+    /// Under a bounded boundary, indices without a partner are left out.
+    /// ```
+    /// use blok::Alignment;
+    /// use blok::order::Boundary;
+    ///
+    /// let align = Alignment::shifted(3, 3, 1, Boundary::Bounded);
+    /// assert_eq!(align, Alignment::wrap(vec![(0,1), (1,2)]));
+    ///
+    /// let align = Alignment::shifted(3, 3, -1, Boundary::Toroidal);
+    /// assert_eq!(align, Alignment::wrap(vec![(0,2), (1,0), (2,1)]));
+    /// ```
+    pub fn shifted(
+        len1: usize,
+        len2: usize,
+        offset: isize,
+        boundary: Boundary
+    ) -> Self {
+
+        let mut vec = Vec::new();
+
+        for i in 0..len1 {
+            if let Some(j) = boundary.resolve(i as isize + offset, len2) {
+                vec.push((i, j))
+            }
+        }
+
+        Self::wrap(vec)
+    }
+
+    /// Align each index to the index N along in the other row, leaving out those past its ends.
+    /// ```
+    /// use blok::{ Alignment, Aligner };
+    ///
+    /// let aligner: Aligner<u8> = Alignment::offset::<_, -1>;
+    /// let align = aligner(&vec![0, 1, 2], &vec![0, 1, 2]);
+    /// assert_eq!(align, Alignment::wrap(vec![(1,0), (2,1)]));
+    /// ```
+    pub fn offset<T, const N: isize>(
+        row1: &Vec<T>,
+        row2: &Vec<T>
+    ) -> Self {
+        Self::shifted(row1.len(), row2.len(), N, Boundary::Bounded)
+    }
+
+    /// Align each index to the index N along in the other row,
+    /// wrapping around its ends as in a toroidal topology.
+    /// ```
+    /// use blok::{ Alignment, Aligner };
+    ///
+    /// let aligner: Aligner<u8> = Alignment::wrapped::<_, 1>;
+    /// let align = aligner(&vec![0, 1, 2], &vec![0, 1, 2]);
+    /// assert_eq!(align, Alignment::wrap(vec![(0,1), (1,2), (2,0)]));
+    /// ```
+    pub fn wrapped<T, const N: isize>(
+        row1: &Vec<T>,
+        row2: &Vec<T>
+    ) -> Self {
+        Self::shifted(row1.len(), row2.len(), N, Boundary::Toroidal)
+    }
+
+    /// Align each index to the index N along in the other row,
+    /// mirroring at its ends as in a reflective topology.
+    /// ```
+    /// use blok::{ Alignment, Aligner };
+    ///
+    /// let aligner: Aligner<u8> = Alignment::reflected::<_, 1>;
+    /// let align = aligner(&vec![0, 1, 2], &vec![0, 1, 2]);
+    /// assert_eq!(align, Alignment::wrap(vec![(0,1), (1,2), (2,2)]));
+    /// ```
+    pub fn reflected<T, const N: isize>(
+        row1: &Vec<T>,
+        row2: &Vec<T>
+    ) -> Self {
+        Self::shifted(row1.len(), row2.len(), N, Boundary::Reflective)
+    }
+
     /// Returns an empty alignment.
     /// For when you do not want to make connections.
     /// ```
@@ -0,0 +1,115 @@
+
+use std::ops::Range;
+
+use serde::{ Serialize, Deserialize };
+
+/// Names a direction within a structure.
+/// X runs across the rows of a layer, Y along the blocks of a row,
+/// and Z through the layers of a stack.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Axis {
+    /// Rows within a layer.
+    X,
+    /// Blocks within a row.
+    Y,
+    /// Layers within a stack.
+    Z,
+}
+
+impl Axis {
+
+    /// List every axis.
+    pub fn all() -> [Axis; 3] {
+        [Axis::X, Axis::Y, Axis::Z]
+    }
+
+}
+
+/// Transforms that take the axis as an argument, so that it can be chosen at runtime,
+/// and so that generic code can treat stacks, layers and rows alike.
+/// Each method calls the version named for its axis, such as `flip_x` for `Axis::X`.
+/// Returns an error if the structure does not have the axis:
+/// layers have no Z axis, and rows only have a Y axis.
+///
+/// Where a type has an inherent method of the same name, such as `Row::offset`,
+/// call the trait method by its path: `AxisTransform::offset(&mut row, Axis::Y, 1)`.
+/// ```
+/// use blok::{ Stack, Layout, layout };
+/// use blok::order::{ Axis, AxisTransform };
+/// # use blok::Block;
+/// # #[derive(Default, Clone)]
+/// # struct MyBlock;
+/// # impl Block for MyBlock {
+/// #     type CreationInstructions = ();
+/// #     fn create(_: &()) -> Self { MyBlock }
+/// #     fn void() -> Self { MyBlock }
+/// #     fn is_void(&self) -> bool { false }
+/// # }
+///
+/// let mut stack = Stack::<MyBlock>::new();
+/// stack.populate(vec![layout![1, 2]; 2], &());
+///
+/// for axis in Axis::all() {
+///     stack.mirror(axis).unwrap();
+/// }
+/// assert_eq!(stack.layouts(), &vec![layout![2, 4, 4, 2]; 4]);
+/// ```
+pub trait AxisTransform: Sized {
+
+    /// Split the structure in two along the axis, returning the part from the given index onward.
+    /// The returned part is empty if the index is past the end of the axis.
+    fn split(&mut self, axis: Axis, at: usize) -> anyhow::Result<Self>;
+
+    /// Reverse the order of elements along the axis.
+    fn flip(&mut self, axis: Axis) -> anyhow::Result<&mut Self>;
+
+    /// Join the other structure to the end of this one along the axis.
+    fn stitch(&mut self, axis: Axis, other: &mut Self) -> anyhow::Result<&mut Self>;
+
+    /// Stitch a flipped clone to the end of this structure along the axis.
+    fn mirror(&mut self, axis: Axis) -> anyhow::Result<&mut Self>;
+
+    /// Merge with the other structure by alternating elements along the axis,
+    /// beginning with an element from this one.
+    fn riffle(&mut self, axis: Axis, other: &mut Self) -> anyhow::Result<&mut Self>;
+
+    /// Add empty space to the start of the axis.
+    fn offset(&mut self, axis: Axis, n: usize) -> anyhow::Result<&mut Self>;
+
+    /// Add empty space to the end of the axis.
+    fn pad(&mut self, axis: Axis, n: usize) -> anyhow::Result<&mut Self>;
+
+    /// Move every element n places along the axis, toward the end if n is positive,
+    /// wrapping elements that pass one end around to the other.
+    fn roll(&mut self, axis: Axis, n: isize) -> anyhow::Result<&mut Self>;
+
+    /// Move every element n places along the axis, toward the end if n is positive.
+    /// Elements that pass one end are dropped, and the positions they leave are filled with voids.
+    fn shift(&mut self, axis: Axis, n: isize) -> anyhow::Result<&mut Self>;
+
+}
+
+/// Rotate the items n places, toward the end if n is positive.
+/// Returns the range of positions filled by items that wrapped around,
+/// which covers every position once n reaches the length of the slice.
+pub(crate) fn roll_slice<T>(items: &mut [T], n: isize) -> Range<usize> {
+    let len = items.len();
+    if len == 0 {
+        return 0..0
+    }
+
+    let k = n.unsigned_abs();
+    if n >= 0 {
+        items.rotate_right(k % len);
+        0..k.min(len)
+    } else {
+        items.rotate_left(k % len);
+        len - k.min(len)..len
+    }
+}
+
+/// Create the error for an axis that a structure does not have.
+pub(crate) fn missing_axis(structure: &str, axis: Axis) -> anyhow::Error {
+    anyhow::anyhow!("{} does not have a {:?} axis", structure, axis)
+}
+
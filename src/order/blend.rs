@@ -0,0 +1,56 @@
+
+use crate::Block;
+
+/// Closure combining an existing block with a pasted one.
+pub type Merge<'m, B> = Box<dyn Fn(&B, &B) -> B + 'm>;
+
+/// Decides how a pasted block is combined with the block already at its position.
+pub enum BlendMode<'m, B: Block> {
+    /// Replace the existing block, even with a void.
+    Overwrite,
+    /// Replace the existing block only if it is void.
+    FillVoids,
+    /// Never replace existing blocks.
+    /// Pasted blocks only fill positions that the target grew to hold them.
+    KeepExisting,
+    /// Replace the existing block with the result of a closure,
+    /// given the existing block, then the pasted one.
+    Custom(Merge<'m, B>),
+}
+
+impl<'m, B: Block> BlendMode<'m, B> {
+
+    /// Combine blocks with a closure, given the existing block, then the pasted one.
+    /// ```
+    /// use blok::order::BlendMode;
+    ///
+    /// let add = BlendMode::custom(|a: &u8, b: &u8| a + b);
+    /// let mut existing = 1u8;
+    /// add.blend(&mut existing, &2, false);
+    /// assert_eq!(existing, 3);
+    /// ```
+    pub fn custom<F: Fn(&B, &B) -> B + 'm>(merge: F) -> Self {
+        Self::Custom(Box::new(merge))
+    }
+
+    /// Combine a pasted block with the existing block at its position.
+    /// `grown` is true when the existing block is a void added to make room for the pasted one.
+    pub fn blend(&self, existing: &mut B, pasted: &B, grown: bool) {
+        match self {
+            Self::Overwrite => *existing = pasted.clone(),
+            Self::FillVoids => if existing.is_void() { *existing = pasted.clone() },
+            Self::KeepExisting => if grown { *existing = pasted.clone() },
+            Self::Custom(merge) => *existing = merge(existing, pasted),
+        }
+    }
+
+}
+
+/// Paste a block into a row at the given index, growing the row with voids to reach it.
+pub(crate) fn paste_block<B: Block>(row: &mut Vec<B>, b: usize, block: &B, mode: &BlendMode<B>) {
+    let grown = b >= row.len();
+    if grown {
+        row.resize(b + 1, B::void());
+    }
+    mode.blend(&mut row[b], block, grown);
+}
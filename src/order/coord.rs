@@ -0,0 +1,18 @@
+
+/// Position of a block within a stack, given as (layer, row, block).
+/// Layers and rows use `0` for the missing levels, 
+/// so a block in a layer is found at (0, r, b), and one in a row at (0, 0, b).
+pub type Coord = (usize, usize, usize);
+
+
+/// List the coordinates of blocks in flat order, given the layouts of each layer.
+pub(crate) fn flat_coords(layouts: &[super::Layout]) -> Vec<Coord> {
+    layouts.iter()
+        .enumerate()
+        .flat_map(|(l, layout)| {
+            layout.iter()
+                .enumerate()
+                .flat_map(move |(r, &len)| (0..len).map(move |b| (l, r, b)))
+        })
+        .collect()
+}
@@ -0,0 +1,314 @@
+
+use std::collections::BTreeSet;
+
+use serde::{ Serialize, Deserialize };
+
+use crate::{ Block, Row, Layer, Stack, Coord };
+use super::coord::flat_coords;
+
+/// Identifies a block durably, regardless of where it is moved.
+/// The generation distinguishes handles that reuse the slot of a released handle.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct Handle {
+    slot: usize,
+    generation: u32,
+}
+
+/// Issues handles, reusing the slots of released handles under a new generation.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HandleAllocator {
+    generations: Vec<u32>,
+    free: Vec<usize>,
+}
+
+impl HandleAllocator {
+
+    /// Create an allocator that has not issued any handles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Issue a new handle.
+    pub fn allocate(&mut self) -> Handle {
+        match self.free.pop() {
+            Some(slot) => Handle { slot, generation: self.generations[slot] },
+            None => {
+                self.generations.push(0);
+                Handle { slot: self.generations.len() - 1, generation: 0 }
+            }
+        }
+    }
+
+    /// Retire a handle so that its slot can be reused.
+    /// Returns false if the handle was not live.
+    pub fn release(&mut self, handle: Handle) -> bool {
+        if !self.is_live(handle) {
+            return false
+        }
+
+        self.generations[handle.slot] += 1;
+        self.free.push(handle.slot);
+        true
+    }
+
+    /// Check whether a handle has been issued and not released.
+    pub fn is_live(&self, handle: Handle) -> bool {
+        self.generations.get(handle.slot) == Some(&handle.generation) &&
+        !self.free.contains(&handle.slot)
+    }
+
+}
+
+/// Wraps a block with a handle, which it keeps through every build and transform operation.
+/// Blocks created by an operation, such as voids, have no handle until one is assigned.
+/// Cloned blocks keep the handle of the original, so copies are given new handles on assignment.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Handled<B: Block> {
+    /// The wrapped block.
+    pub block: B,
+    /// Handle identifying the block, if one has been assigned.
+    pub handle: Option<Handle>,
+}
+
+impl<B: Block> Handled<B> {
+
+    /// Wrap a block without a handle.
+    pub fn new(block: B) -> Self {
+        Self { block, handle: None }
+    }
+
+}
+
+impl<B: Block> Block for Handled<B> {
+
+    type CreationInstructions = B::CreationInstructions;
+
+    fn create(instructions: &Self::CreationInstructions) -> Self {
+        Self::new(B::create(instructions))
+    }
+
+    fn void() -> Self {
+        Self::new(B::void())
+    }
+
+    fn is_void(&self) -> bool {
+        self.block.is_void()
+    }
+
+}
+
+/// Give a handle to each block without one, and to each copy of a block seen earlier.
+/// Returns the number of handles assigned.
+fn assign_helper<B: Block>(blocks: &mut [Handled<B>], allocator: &mut HandleAllocator) -> usize {
+    let mut seen = BTreeSet::new();
+    let mut assigned = 0;
+    for block in blocks.iter_mut() {
+        match block.handle {
+            Some(handle) if seen.insert(handle) => {},
+            _ => {
+                let handle = allocator.allocate();
+                seen.insert(handle);
+                block.handle = Some(handle);
+                assigned += 1;
+            }
+        }
+    }
+    assigned
+}
+
+/// Find the flat index of the block with the given handle.
+fn find_helper<B: Block>(blocks: &[Handled<B>], handle: Handle) -> Option<usize> {
+    blocks.iter().position(|block| block.handle == Some(handle))
+}
+
+/// Methods for using handles with a stack:
+impl<B: Block> Stack<Handled<B>> {
+
+    /// Give a handle to each block without one, and to each copy of a block seen earlier.
+    /// Returns the number of handles assigned.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// use blok::order::{ Handled, HandleAllocator };
+    /// # use blok::Block;
+    /// # #[derive(Default, Clone)]
+    /// # struct MyBlock { value: u8 }
+    /// # impl Block for MyBlock {
+    /// #     type CreationInstructions = u8;
+    /// #     fn create(value: &u8) -> Self { MyBlock { value: *value } }
+    /// #     fn void() -> Self { Self::default() }
+    /// #     fn is_void(&self) -> bool { self.value == 0 }
+    /// # }
+    ///
+    /// let mut allocator = HandleAllocator::new();
+    /// let mut stack = Stack::<Handled<MyBlock>>::new();
+    /// stack.populate(vec![layout![2, 1]], &1);
+    /// stack.assign_handles(&mut allocator);
+    ///
+    /// let handle = stack.blocks()[2].handle.unwrap();
+    /// stack.flip_x();
+    /// assert_eq!(stack.coord_of(handle), Some((0, 0, 0)));
+    ///
+    /// stack.get_by_handle_mut(handle).unwrap().value = 5;
+    /// assert_eq!(stack.blocks()[0].block.value, 5);
+    /// ```
+    pub fn assign_handles(&mut self, allocator: &mut HandleAllocator) -> usize {
+        assign_helper(self.blocks_mut(), allocator)
+    }
+
+    /// Get a reference to the block with the given handle.
+    pub fn get_by_handle(&self, handle: Handle) -> Option<&B> {
+        let index = find_helper(self.blocks(), handle)?;
+        Some(&self.blocks()[index].block)
+    }
+
+    /// Get a mutable reference to the block with the given handle.
+    pub fn get_by_handle_mut(&mut self, handle: Handle) -> Option<&mut B> {
+        let index = find_helper(self.blocks(), handle)?;
+        Some(&mut self.blocks_mut()[index].block)
+    }
+
+    /// Find the coordinates of the block with the given handle.
+    pub fn coord_of(&self, handle: Handle) -> Option<Coord> {
+        let index = find_helper(self.blocks(), handle)?;
+        Some(flat_coords(self.layouts())[index])
+    }
+
+}
+
+/// Methods for using handles with a layer:
+impl<B: Block> Layer<Handled<B>> {
+
+    /// Give a handle to each block without one, and to each copy of a block seen earlier.
+    /// Returns the number of handles assigned.
+    pub fn assign_handles(&mut self, allocator: &mut HandleAllocator) -> usize {
+        assign_helper(self.blocks_mut(), allocator)
+    }
+
+    /// Get a reference to the block with the given handle.
+    pub fn get_by_handle(&self, handle: Handle) -> Option<&B> {
+        let index = find_helper(self.blocks(), handle)?;
+        Some(&self.blocks()[index].block)
+    }
+
+    /// Get a mutable reference to the block with the given handle.
+    pub fn get_by_handle_mut(&mut self, handle: Handle) -> Option<&mut B> {
+        let index = find_helper(self.blocks(), handle)?;
+        Some(&mut self.blocks_mut()[index].block)
+    }
+
+    /// Find the coordinates of the block with the given handle, as (0, r, b).
+    pub fn coord_of(&self, handle: Handle) -> Option<Coord> {
+        let index = find_helper(self.blocks(), handle)?;
+        Some(flat_coords(&[self.layout().clone()])[index])
+    }
+
+}
+
+/// Methods for using handles with a row:
+impl<B: Block> Row<Handled<B>> {
+
+    /// Give a handle to each block without one, and to each copy of a block seen earlier.
+    /// Returns the number of handles assigned.
+    pub fn assign_handles(&mut self, allocator: &mut HandleAllocator) -> usize {
+        assign_helper(&mut self.blocks, allocator)
+    }
+
+    /// Get a reference to the block with the given handle.
+    pub fn get_by_handle(&self, handle: Handle) -> Option<&B> {
+        let index = find_helper(self.blocks(), handle)?;
+        Some(&self.blocks[index].block)
+    }
+
+    /// Get a mutable reference to the block with the given handle.
+    pub fn get_by_handle_mut(&mut self, handle: Handle) -> Option<&mut B> {
+        let index = find_helper(self.blocks(), handle)?;
+        Some(&mut self.blocks[index].block)
+    }
+
+    /// Find the coordinates of the block with the given handle, as (0, 0, b).
+    pub fn coord_of(&self, handle: Handle) -> Option<Coord> {
+        find_helper(self.blocks(), handle).map(|b| (0, 0, b))
+    }
+
+}
+
+impl<B: Block> Stack<B> {
+
+    /// Wrap each block of the stack with a new handle.
+    pub fn with_handles(&self, allocator: &mut HandleAllocator) -> Stack<Handled<B>> {
+        let mut stack = self.map(|block| Handled::new(block.clone()));
+        stack.assign_handles(allocator);
+        stack
+    }
+
+}
+
+impl<B: Block> Layer<B> {
+
+    /// Wrap each block of the layer with a new handle.
+    pub fn with_handles(&self, allocator: &mut HandleAllocator) -> Layer<Handled<B>> {
+        let mut layer = self.map(|block| Handled::new(block.clone()));
+        layer.assign_handles(allocator);
+        layer
+    }
+
+}
+
+impl<B: Block> Row<B> {
+
+    /// Wrap each block of the row with a new handle.
+    pub fn with_handles(&self, allocator: &mut HandleAllocator) -> Row<Handled<B>> {
+        let mut row = self.map(|block| Handled::new(block.clone()));
+        row.assign_handles(allocator);
+        row
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+    use crate::types::stack::test::test_stack;
+
+    /// Test that released handles are not confused with their replacements.
+    #[test] fn allocator_test() {
+        let mut allocator = HandleAllocator::new();
+        let a = allocator.allocate();
+        let b = allocator.allocate();
+        assert!(allocator.release(a));
+        assert!(!allocator.release(a));
+
+        let c = allocator.allocate();
+        assert_ne!(a, c);
+        assert!(!allocator.is_live(a));
+        assert!(allocator.is_live(b) && allocator.is_live(c));
+    }
+
+    /// Test that handles follow blocks through transforms.
+    #[test] fn stack_handle_test() {
+        let mut allocator = HandleAllocator::new();
+        let mut stack = test_stack().with_handles(&mut allocator);
+        let handle = stack.blocks()[4].handle.unwrap();
+        assert_eq!(stack.coord_of(handle), Some((1, 1, 0)));
+
+        stack.flip_z();
+        stack.flip_y();
+        assert_eq!(stack.coord_of(handle), Some((1, 1, 1)));
+        assert_eq!(stack.get_by_handle(handle).unwrap().id, stack.blocks()[5].block.id);
+
+        // Copies are given their own handles, while originals keep theirs.
+        stack.mirror_z();
+        assert_eq!(stack.assign_handles(&mut allocator), 9);
+        assert_eq!(stack.coord_of(handle), Some((1, 1, 1)));
+
+        let mut layer = stack.clone_into_layers().remove(0);
+        let first = layer.blocks()[0].handle.unwrap();
+        layer.flip_x();
+        assert_eq!(layer.coord_of(first), Some((0, 1, 0)));
+    }
+
+}
+
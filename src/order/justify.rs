@@ -0,0 +1,53 @@
+
+use serde::{ Serialize, Deserialize };
+
+use crate::Block;
+
+/// Where the non-void blocks of a row are moved when it is justified,
+/// like aligning the lines of a paragraph.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Justify {
+    /// Move blocks to the start of the row.
+    #[default]
+    Start,
+    /// Move blocks to the end of the row.
+    End,
+    /// Move blocks to the middle of the row.
+    /// When the voids cannot be split evenly, the extra void goes at the end.
+    Center,
+}
+
+impl Justify {
+
+    /// Count the voids placed before and after the given number of blocks
+    /// to fill a row of the given width.
+    /// ```
+    /// use blok::order::Justify;
+    ///
+    /// assert_eq!(Justify::Start.padding(2, 5), (0, 3));
+    /// assert_eq!(Justify::End.padding(2, 5), (3, 0));
+    /// assert_eq!(Justify::Center.padding(2, 5), (1, 2));
+    /// ```
+    pub fn padding(&self, blocks: usize, width: usize) -> (usize, usize) {
+        let space = width.saturating_sub(blocks);
+        match self {
+            Self::Start => (0, space),
+            Self::End => (space, 0),
+            Self::Center => (space / 2, space - space / 2),
+        }
+    }
+
+}
+
+/// Keep the non-void blocks of the row in order, padding them with voids to the given width.
+pub(crate) fn justify_blocks<B: Block>(row: Vec<B>, width: usize, justify: Justify) -> Vec<B> {
+    let blocks: Vec<B> = row.into_iter()
+        .filter(|block| !block.is_void())
+        .collect();
+
+    let (before, after) = justify.padding(blocks.len(), width);
+    let mut justified = vec![B::void(); before];
+    justified.extend(blocks);
+    justified.extend(vec![B::void(); after]);
+    justified
+}
@@ -5,3 +5,26 @@ pub use align::{ Alignment, Aligner };
 pub mod layout;
 pub use layout::Layout;
 
+pub mod coord;
+pub use coord::Coord;
+
+pub mod remap;
+pub use remap::{ Remap, Tracked };
+
+pub mod handle;
+pub use handle::{ Handle, HandleAllocator, Handled };
+
+pub mod axis;
+pub use axis::{ Axis, AxisTransform };
+
+pub mod topology;
+pub use topology::{ Boundary, Topology, Connectivity, Offset, FACE_OFFSETS };
+
+pub mod justify;
+pub use justify::Justify;
+
+pub mod blend;
+pub use blend::BlendMode;
+
+pub mod selection;
+pub use selection::Selection;
@@ -0,0 +1,340 @@
+
+use std::collections::BTreeMap;
+
+use crate::{ Block, Row, Layer, Stack, Layout, Coord };
+use crate::connect::ConnectionMap;
+use super::coord::flat_coords;
+
+/// Wraps a block with the flat index it had before a transform,
+/// so that blocks can be followed as they are moved.
+/// Blocks created during the transform, such as voids or blocks from other structures,
+/// have no origin.
+#[derive(Debug, Default, Clone)]
+pub struct Tracked<B: Block> {
+    /// The wrapped block.
+    pub block: B,
+    /// Flat index of the block before the transform, if it existed.
+    pub origin: Option<usize>,
+}
+
+impl<B: Block> Tracked<B> {
+
+    /// Wrap a block with no origin, as a block created during the transform.
+    pub fn new(block: B) -> Self {
+        Self { block, origin: None }
+    }
+
+    /// Wrap a block with its flat index from before the transform.
+    pub fn at(block: B, origin: usize) -> Self {
+        Self { block, origin: Some(origin) }
+    }
+
+}
+
+impl<B: Block> Block for Tracked<B> {
+
+    type CreationInstructions = B::CreationInstructions;
+
+    fn create(instructions: &Self::CreationInstructions) -> Self {
+        Self::new(B::create(instructions))
+    }
+
+    fn void() -> Self {
+        Self::new(B::void())
+    }
+
+    fn is_void(&self) -> bool {
+        self.block.is_void()
+    }
+
+}
+
+/// Records where each block went during a transform.
+/// Blocks are found by coordinates or by flat index.
+/// A block that was copied is mapped to its first position in the result,
+/// and later copies are counted as created.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Remap {
+    old_coords: Vec<Coord>,
+    new_coords: Vec<Coord>,
+    /// New flat index for each old flat index, or None if the block was removed.
+    forward: Vec<Option<usize>>,
+    /// Old flat index for each new flat index, or None if the block was created.
+    origins: Vec<Option<usize>>,
+}
+
+impl Remap {
+
+    /// Build a remap from the layouts before and after a transform,
+    /// and the origin of each block after it.
+    pub fn new(old: &[Layout], new: &[Layout], origins: Vec<Option<usize>>) -> Self {
+        let old_coords = flat_coords(old);
+        let new_coords = flat_coords(new);
+
+        let mut forward = vec![None; old_coords.len()];
+        let mut firsts = Vec::with_capacity(origins.len());
+        for (index, origin) in origins.iter().enumerate() {
+            let first = origin
+                .filter(|&o| o < forward.len() && forward[o].is_none())
+                .inspect(|&o| forward[o] = Some(index));
+            firsts.push(first);
+        }
+
+        Self { old_coords, new_coords, forward, origins: firsts }
+    }
+
+    /// Get the new flat index of a block from its old flat index.
+    /// Returns None if the block was removed or the index did not exist.
+    pub fn index(&self, old: usize) -> Option<usize> {
+        self.forward.get(old).copied().flatten()
+    }
+
+    /// Get the new coordinates of a block from its old coordinates.
+    /// Returns None if the block was removed or the coordinates did not exist.
+    pub fn coord(&self, old: Coord) -> Option<Coord> {
+        let old = self.old_coords.binary_search(&old).ok()?;
+        self.index(old).map(|new| self.new_coords[new])
+    }
+
+    /// Get the old coordinates of a block from its new coordinates.
+    /// Returns None if the block was created by the transform.
+    pub fn origin(&self, new: Coord) -> Option<Coord> {
+        let new = self.new_coords.binary_search(&new).ok()?;
+        self.origins[new].map(|old| self.old_coords[old])
+    }
+
+    /// List the old coordinates of blocks that were removed.
+    pub fn removed(&self) -> Vec<Coord> {
+        self.forward.iter()
+            .zip(self.old_coords.iter())
+            .filter(|(new, _)| new.is_none())
+            .map(|(_, coord)| *coord)
+            .collect()
+    }
+
+    /// List the new coordinates of blocks that were created, including copies.
+    pub fn created(&self) -> Vec<Coord> {
+        self.origins.iter()
+            .zip(self.new_coords.iter())
+            .filter(|(old, _)| old.is_none())
+            .map(|(_, coord)| *coord)
+            .collect()
+    }
+
+    /// Move a collection of coordinates to their new positions, dropping those that were removed.
+    pub fn apply(&self, coords: impl IntoIterator<Item = Coord>) -> Vec<Coord> {
+        coords.into_iter()
+            .filter_map(|coord| self.coord(coord))
+            .collect()
+    }
+
+    /// Move the keys of a map to their new positions, dropping entries that were removed.
+    pub fn apply_keys<V>(&self, map: BTreeMap<Coord, V>) -> BTreeMap<Coord, V> {
+        map.into_iter()
+            .filter_map(|(coord, value)| Some((self.coord(coord)?, value)))
+            .collect()
+    }
+
+    /// Move the connections in a map to their new positions,
+    /// dropping connections to or from blocks that were removed.
+    pub fn apply_connections<I: Clone>(&self, map: &ConnectionMap<I>) -> ConnectionMap<I> {
+        let mut remapped = ConnectionMap::new();
+        for (from, to, instructions) in map.edges() {
+            if let (Some(from), Some(to)) = (self.coord(from), self.coord(to)) {
+                remapped.connect(from, to, instructions.clone());
+            }
+        }
+        remapped
+    }
+
+}
+
+/// Wrap each block with its flat index.
+fn track_helper<B: Block>(blocks: Vec<B>) -> Vec<Tracked<B>> {
+    blocks.into_iter()
+        .enumerate()
+        .map(|(i, block)| Tracked::at(block, i))
+        .collect()
+}
+
+/// Separate the blocks from their origins.
+fn untrack_helper<B: Block>(tracked: Vec<Tracked<B>>) -> (Vec<B>, Vec<Option<usize>>) {
+    tracked.into_iter()
+        .map(|t| (t.block, t.origin))
+        .unzip()
+}
+
+impl<B: Block> Stack<B> {
+
+    /// Run a transform over this stack and record where each block went.
+    /// Blocks are tracked for the duration of the transform,
+    /// so any other structures used by it must hold tracked blocks as well.
+    /// Returns the transform's result along with the remap.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// # use blok::Block;
+    /// # #[derive(Default, Clone)]
+    /// # struct MyBlock { void: bool }
+    /// # impl Block for MyBlock {
+    /// #     type CreationInstructions = ();
+    /// #     fn create(_: &()) -> Self { MyBlock { void: false } }
+    /// #     fn void() -> Self { MyBlock { void: true } }
+    /// #     fn is_void(&self) -> bool { self.void }
+    /// # }
+    ///
+    /// let mut stack = Stack::<MyBlock>::new();
+    /// stack.populate(vec![layout![1, 2], layout![3]], &());
+    ///
+    /// let (_, remap) = stack.remap(|s| s.flip_x());
+    /// assert_eq!(remap.coord((0, 0, 0)), Some((0, 1, 0)));
+    /// assert_eq!(remap.coord((0, 1, 1)), Some((0, 0, 1)));
+    /// assert_eq!(remap.apply(vec![(1, 0, 2), (5, 0, 0)]), vec![(1, 0, 2)]);
+    ///
+    /// let (_, remap) = stack.remap(|s| { s.realize_voids(); });
+    /// assert_eq!(remap.created(), vec![(0, 0, 2), (0, 1, 1), (0, 1, 2), (1, 1, 0), (1, 1, 1), (1, 1, 2)]);
+    /// ```
+    pub fn remap<R>(&mut self, transform: impl FnOnce(&mut Stack<Tracked<B>>) -> R) -> (R, Remap) {
+        let old = self.layouts().clone();
+        let mut tracked = Stack::<Tracked<B>>::new();
+        *tracked.layouts_mut() = std::mem::take(self.layouts_mut());
+        *tracked.blocks_mut() = track_helper(std::mem::take(self.blocks_mut()));
+
+        let result = transform(&mut tracked);
+
+        let (blocks, origins) = untrack_helper(std::mem::take(tracked.blocks_mut()));
+        *self.layouts_mut() = std::mem::take(tracked.layouts_mut());
+        *self.blocks_mut() = blocks;
+
+        (result, Remap::new(&old, self.layouts(), origins))
+    }
+
+}
+
+impl<B: Block> Stack<Tracked<B>> {
+
+    /// Unwrap the blocks of a tracked stack, such as one split off during a transform.
+    pub fn untrack(self) -> Stack<B> {
+        self.map(|t| t.block.clone())
+    }
+
+}
+
+impl<B: Block> Layer<B> {
+
+    /// Run a transform over this layer and record where each block went.
+    /// Layers use coordinates (0, r, b). See `Stack::remap`.
+    pub fn remap<R>(&mut self, transform: impl FnOnce(&mut Layer<Tracked<B>>) -> R) -> (R, Remap) {
+        let old = self.layout().clone();
+        let mut tracked = Layer::<Tracked<B>>::new();
+        *tracked.layout_mut() = std::mem::take(self.layout_mut());
+        *tracked.blocks_mut() = track_helper(std::mem::take(self.blocks_mut()));
+
+        let result = transform(&mut tracked);
+
+        let (blocks, origins) = untrack_helper(std::mem::take(tracked.blocks_mut()));
+        *self.layout_mut() = std::mem::take(tracked.layout_mut());
+        *self.blocks_mut() = blocks;
+
+        let remap = Remap::new(&[old], &[self.layout().clone()], origins);
+        (result, remap)
+    }
+
+}
+
+impl<B: Block> Layer<Tracked<B>> {
+
+    /// Unwrap the blocks of a tracked layer, such as one split off during a transform.
+    pub fn untrack(self) -> Layer<B> {
+        self.map(|t| t.block.clone())
+    }
+
+}
+
+impl<B: Block> Row<B> {
+
+    /// Run a transform over this row and record where each block went.
+    /// Rows use coordinates (0, 0, b). See `Stack::remap`.
+    pub fn remap<R>(&mut self, transform: impl FnOnce(&mut Row<Tracked<B>>) -> R) -> (R, Remap) {
+        let old = Layout::wrap(vec![self.len()]);
+        let mut tracked = Row::wrap(track_helper(std::mem::take(&mut self.blocks)));
+
+        let result = transform(&mut tracked);
+
+        let (blocks, origins) = untrack_helper(std::mem::take(&mut tracked.blocks));
+        self.blocks = blocks;
+
+        let remap = Remap::new(&[old], &[Layout::wrap(vec![self.len()])], origins);
+        (result, remap)
+    }
+
+}
+
+impl<B: Block> Row<Tracked<B>> {
+
+    /// Unwrap the blocks of a tracked row, such as one split off during a transform.
+    pub fn untrack(self) -> Row<B> {
+        self.map(|t| t.block.clone())
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+    use crate::layout;
+    use crate::block::test::TestBlock;
+    use crate::types::stack::test::test_stack;
+
+    /// Test remapping a transform that moves blocks between layers.
+    #[test] fn remap_stack_test() {
+        let mut stack = test_stack();
+        let (_, remap) = stack.remap(|s| s.flip_z());
+
+        assert_eq!(remap.coord((0, 1, 1)), Some((2, 1, 1)));
+        assert_eq!(remap.origin((2, 1, 1)), Some((0, 1, 1)));
+        assert_eq!(remap.index(0), Some(6));
+        assert!(remap.removed().is_empty() && remap.created().is_empty());
+
+        let mut labels = BTreeMap::new();
+        labels.insert((1, 0, 0), "a");
+        labels.insert((9, 0, 0), "b");
+        assert_eq!(remap.apply_keys(labels).into_iter().collect::<Vec<_>>(), vec![((1, 0, 0), "a")]);
+    }
+
+    /// Test that split blocks are removed, and mirrored copies are created.
+    #[test] fn remap_removed_created_test() {
+        let mut stack = test_stack();
+        let (split, remap) = stack.remap(|s| s.split_z(1));
+        assert_eq!(split.untrack().layouts().len(), 2);
+        assert_eq!(remap.removed().len(), 6);
+        assert_eq!(remap.coord((1, 0, 0)), None);
+
+        let (_, remap) = stack.remap(|s| s.mirror_y());
+        assert_eq!(remap.coord((0, 1, 0)), Some((0, 1, 0)));
+        assert_eq!(remap.created(), vec![(0, 0, 1), (0, 1, 2), (0, 1, 3)]);
+
+        let mut map = ConnectionMap::new();
+        map.connect((0, 0, 0), (0, 1, 1), ());
+        let (_, remap) = stack.remap(|s| s.flip_y());
+        assert!(remap.apply_connections(&map).contains((0, 0, 1), (0, 1, 2)));
+    }
+
+    /// Test remapping layers and rows.
+    #[test] fn remap_layer_row_test() {
+        let mut layer = Layer::<TestBlock>::new();
+        layer.populate(layout![2, 2], &"a".to_string());
+        layer.blocks_mut()[0].to_void();
+        let (_, remap) = layer.remap(|l| { l.compress(); });
+        assert_eq!(remap.removed(), vec![(0, 0, 0)]);
+        assert_eq!(remap.coord((0, 0, 1)), Some((0, 0, 0)));
+
+        let mut row = Row::wrap(vec![TestBlock::create(&"a".to_string()); 3]);
+        let (_, remap) = row.remap(|r| r.reverse());
+        assert_eq!(remap.coord((0, 0, 0)), Some((0, 0, 2)));
+    }
+
+}
+
@@ -0,0 +1,227 @@
+
+use std::collections::{ BTreeSet, VecDeque };
+
+use derive_more::{ Deref, DerefMut };
+use serde::{ Serialize, Deserialize };
+
+use crate::{ Block, Stack, Coord };
+use crate::connect::cascade::index_structure;
+use super::{ Connectivity, Topology };
+
+/// A set of block coordinates within a stack, such as a region found by flood selection.
+#[derive(Debug, Default, Clone, Deref, DerefMut, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Selection(BTreeSet<Coord>);
+
+impl Selection {
+
+    /// Create an empty selection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a selection from an existing set of coordinates.
+    pub fn wrap(set: BTreeSet<Coord>) -> Self {
+        Self(set)
+    }
+
+}
+
+impl FromIterator<Coord> for Selection {
+    fn from_iter<T: IntoIterator<Item = Coord>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// Region selection for stacks:
+impl<B: Block> Stack<B> {
+
+    /// Select the region of blocks that satisfy the predicate,
+    /// spreading from the seed through neighbors given by the connectivity.
+    /// Row lengths are respected, so regions in ragged stacks only spread to blocks that exist.
+    /// Returns an empty selection if the seed is not present or does not satisfy the predicate.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// use blok::order::Connectivity;
+    ///
+    /// // A void cavity, enclosed within a single layer.
+    /// let mut stack = Stack::<u8>::new();
+    /// stack.populate(vec![layout![3, 3, 3]], &1);
+    /// *stack.get_block_mut(0, 1, 1).unwrap() = 0;
+    ///
+    /// let cavity = stack.flood_select((0, 1, 1), |b| *b == 0, Connectivity::Faces);
+    /// assert_eq!(cavity.len(), 1);
+    ///
+    /// let walls = stack.flood_select((0, 0, 0), |b| *b == 1, Connectivity::Faces);
+    /// assert_eq!(walls.len(), 8);
+    /// ```
+    pub fn flood_select(
+        &self,
+        seed: Coord,
+        predicate: impl Fn(&B) -> bool,
+        connectivity: Connectivity
+    ) -> Selection {
+
+        self.flood_select_with_topology(seed, predicate, connectivity, &Topology::bounded())
+    }
+
+    /// Select the region of blocks that satisfy the predicate, as in `flood_select`,
+    /// but let the region spread past the ends of each axis under the given topology.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// use blok::order::{ Connectivity, Topology };
+    ///
+    /// // The two ends of the row only meet if the row wraps around.
+    /// let mut stack = Stack::<u8>::new();
+    /// stack.populate(vec![layout![4]], &1);
+    /// *stack.get_block_mut(0, 0, 1).unwrap() = 0;
+    ///
+    /// let bounded = stack.flood_select((0, 0, 0), |b| *b == 1, Connectivity::Faces);
+    /// assert_eq!(bounded.len(), 1);
+    ///
+    /// let toroidal = stack.flood_select_with_topology(
+    ///     (0, 0, 0), |b| *b == 1, Connectivity::Faces, &Topology::toroidal()
+    /// );
+    /// assert_eq!(toroidal.len(), 3);
+    /// ```
+    pub fn flood_select_with_topology(
+        &self,
+        seed: Coord,
+        predicate: impl Fn(&B) -> bool,
+        connectivity: Connectivity,
+        topology: &Topology
+    ) -> Selection {
+
+        let indexes = index_structure(self.layouts());
+        let index_of = |(l, r, b): Coord| indexes.get(l)?.get(r)?.get(b).copied();
+        let accepts = |coord: Coord| index_of(coord).is_some_and(|i| predicate(&self.blocks()[i]));
+
+        let mut selection = Selection::new();
+        if !accepts(seed) {
+            return selection
+        }
+
+        let offsets = connectivity.offsets();
+        let mut queue = VecDeque::from([seed]);
+        selection.insert(seed);
+
+        while let Some(coord) = queue.pop_front() {
+            for next in topology.neighbors(self.layouts(), coord, &offsets) {
+                if !selection.contains(&next) && accepts(next) {
+                    selection.insert(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        selection
+    }
+
+    /// Replace the region of blocks found by `flood_select` with the result of the closure,
+    /// given each original block. Returns the number of blocks replaced.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// use blok::order::Connectivity;
+    ///
+    /// let mut stack = Stack::<u8>::new();
+    /// stack.populate(vec![layout![2, 2], layout![2, 2]], &1);
+    /// *stack.get_block_mut(0, 0, 1).unwrap() = 0;
+    /// *stack.get_block_mut(0, 1, 0).unwrap() = 0;
+    ///
+    /// // The two voids only touch at an edge, so faces do not connect them.
+    /// let filled = stack.flood_fill((0, 0, 1), |b| *b == 0, |_| 5, Connectivity::Faces);
+    /// assert_eq!(filled, 1);
+    ///
+    /// let filled = stack.flood_fill((0, 0, 0), |b| *b != 0, |_| 0, Connectivity::All);
+    /// assert_eq!(filled, 7);
+    /// assert_eq!(stack.get_block_ref(0, 1, 0), Some(&0));
+    /// ```
+    pub fn flood_fill(
+        &mut self,
+        seed: Coord,
+        predicate: impl Fn(&B) -> bool,
+        replacement: impl Fn(&B) -> B,
+        connectivity: Connectivity
+    ) -> usize {
+
+        self.flood_fill_with_topology(seed, predicate, replacement, connectivity, &Topology::bounded())
+    }
+
+    /// Replace the region of blocks found by `flood_select_with_topology`
+    /// with the result of the closure, given each original block.
+    /// Returns the number of blocks replaced.
+    pub fn flood_fill_with_topology(
+        &mut self,
+        seed: Coord,
+        predicate: impl Fn(&B) -> bool,
+        replacement: impl Fn(&B) -> B,
+        connectivity: Connectivity,
+        topology: &Topology
+    ) -> usize {
+
+        let selection = self.flood_select_with_topology(seed, predicate, connectivity, topology);
+        let indexes = index_structure(self.layouts());
+        for &(l, r, b) in selection.iter() {
+            let block = &mut self.blocks_mut()[indexes[l][r][b]];
+            *block = replacement(block);
+        }
+
+        selection.len()
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+    use crate::{ Layout, layout };
+    use crate::order::{ Axis, Boundary };
+
+    /// Test that regions only spread to blocks that exist in ragged stacks,
+    /// and that corners connect under full connectivity.
+    #[test] fn ragged_flood_test() {
+        let mut stack = Stack::<u8>::new();
+        stack.populate(vec![layout![3, 1], layout![1, 3]], &1);
+
+        let all = stack.flood_select((0, 0, 0), |b| *b == 1, Connectivity::Faces);
+        assert_eq!(all.len(), 8);
+
+        *stack.get_block_mut(0, 0, 1).unwrap() = 0;
+        *stack.get_block_mut(1, 0, 0).unwrap() = 0;
+        let region = stack.flood_select((0, 0, 2), |b| *b == 1, Connectivity::Faces);
+        assert_eq!(region, Selection::from_iter([(0, 0, 2)]));
+
+        // (0, 0, 2) touches (1, 1, 2) at a corner.
+        let region = stack.flood_select((0, 0, 2), |b| *b == 1, Connectivity::All);
+        assert_eq!(region.len(), 6);
+
+        assert!(stack.flood_select((0, 1, 1), |_| true, Connectivity::All).is_empty());
+    }
+
+    /// Test that regions spread past the ends of an axis only where the topology wraps it.
+    #[test] fn topology_flood_test() {
+        let mut stack = Stack::<u8>::new();
+        stack.populate(vec![layout![3, 3]; 3], &1);
+        for r in 0..2 {
+            for b in 0..3 {
+                *stack.get_block_mut(1, r, b).unwrap() = 0;
+            }
+        }
+
+        // The void layer cuts the bottom layer off from the top.
+        let region = stack.flood_select((0, 0, 0), |b| *b == 1, Connectivity::Faces);
+        assert_eq!(region.len(), 6);
+
+        let topology = Topology::bounded().with(Axis::Z, Boundary::Toroidal);
+        let region = stack.flood_select_with_topology((0, 0, 0), |b| *b == 1, Connectivity::Faces, &topology);
+        assert_eq!(region.len(), 12);
+
+        let filled = stack.flood_fill_with_topology((2, 1, 2), |b| *b == 1, |_| 2, Connectivity::Faces, &topology);
+        assert_eq!(filled, 12);
+        assert_eq!(stack.get_block_ref(0, 0, 0), Some(&2));
+        assert_eq!(stack.get_block_ref(1, 0, 0), Some(&0));
+    }
+
+}
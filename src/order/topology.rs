@@ -0,0 +1,309 @@
+
+use serde::{ Serialize, Deserialize };
+
+use crate::{ Block, Layer, Stack, Coord };
+use super::{ Axis, Layout };
+
+/// Relative position of a block, given as (layers, rows, blocks) like a `Coord`.
+pub type Offset = (isize, isize, isize);
+
+/// Offsets to the six blocks sharing a face with a block.
+pub const FACE_OFFSETS: [Offset; 6] = [
+    (-1, 0, 0), (1, 0, 0),
+    (0, -1, 0), (0, 1, 0),
+    (0, 0, -1), (0, 0, 1),
+];
+
+/// Decides which nearby blocks count as neighbors when spreading through a structure.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Connectivity {
+    /// The six blocks sharing a face.
+    #[default]
+    Faces,
+    /// All twenty-six blocks sharing a face, edge, or corner.
+    All,
+}
+
+impl Connectivity {
+
+    /// List the offsets to each neighbor.
+    /// ```
+    /// use blok::order::Connectivity;
+    ///
+    /// assert_eq!(Connectivity::Faces.offsets().len(), 6);
+    /// assert_eq!(Connectivity::All.offsets().len(), 26);
+    /// ```
+    pub fn offsets(&self) -> Vec<Offset> {
+        match self {
+            Self::Faces => FACE_OFFSETS.to_vec(),
+            Self::All => {
+                let mut offsets = Vec::new();
+                for l in -1..=1 {
+                    for r in -1..=1 {
+                        for b in -1..=1 {
+                            if (l, r, b) != (0, 0, 0) {
+                                offsets.push((l, r, b));
+                            }
+                        }
+                    }
+                }
+                offsets
+            },
+        }
+    }
+
+}
+
+/// Decides what lies past the ends of an axis.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Boundary {
+    /// Nothing lies past the ends.
+    #[default]
+    Bounded,
+    /// The ends wrap around to each other, so that the first and last positions are neighbors.
+    Toroidal,
+    /// The axis is mirrored at its ends, so that stepping past the last position
+    /// returns to it, then moves back toward the first.
+    Reflective,
+}
+
+impl Boundary {
+
+    /// Find the position reached from the start of an axis of the given length,
+    /// or None if it lies outside of a bounded axis, or the axis is empty.
+    /// ```
+    /// use blok::order::Boundary;
+    ///
+    /// assert_eq!(Boundary::Bounded.resolve(3, 3), None);
+    /// assert_eq!(Boundary::Toroidal.resolve(-1, 3), Some(2));
+    /// assert_eq!(Boundary::Reflective.resolve(3, 3), Some(2));
+    /// assert_eq!(Boundary::Reflective.resolve(-2, 3), Some(1));
+    /// ```
+    pub fn resolve(&self, index: isize, len: usize) -> Option<usize> {
+        if len == 0 {
+            return None
+        }
+
+        let len = len as isize;
+        match self {
+            Self::Bounded => (0..len).contains(&index).then_some(index as usize),
+            Self::Toroidal => Some(index.rem_euclid(len) as usize),
+            Self::Reflective => {
+                let period = index.rem_euclid(2 * len);
+                Some(if period < len { period } else { 2 * len - 1 - period } as usize)
+            },
+        }
+    }
+
+}
+
+/// Boundaries for each axis of a structure, respected by neighbor queries,
+/// offset aligners, and spatial connection plans.
+/// Axes follow the transforms: z is the layer, x is the row, and y is the block in the row.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Topology {
+    /// Boundary across the rows of a layer.
+    pub x: Boundary,
+    /// Boundary along the blocks of a row.
+    pub y: Boundary,
+    /// Boundary through the layers of a stack.
+    pub z: Boundary,
+}
+
+impl Topology {
+
+    /// Create a topology with the given boundary on each axis.
+    pub fn new(x: Boundary, y: Boundary, z: Boundary) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Create a topology with the same boundary on every axis.
+    pub fn uniform(boundary: Boundary) -> Self {
+        Self::new(boundary, boundary, boundary)
+    }
+
+    /// Create a topology where nothing lies past the ends of any axis.
+    pub fn bounded() -> Self {
+        Self::uniform(Boundary::Bounded)
+    }
+
+    /// Create a topology where every axis wraps around, for periodic boundary conditions.
+    pub fn toroidal() -> Self {
+        Self::uniform(Boundary::Toroidal)
+    }
+
+    /// Create a topology where every axis is mirrored at its ends.
+    pub fn reflective() -> Self {
+        Self::uniform(Boundary::Reflective)
+    }
+
+    /// Set the boundary of a single axis.
+    pub fn with(mut self, axis: Axis, boundary: Boundary) -> Self {
+        match axis {
+            Axis::X => self.x = boundary,
+            Axis::Y => self.y = boundary,
+            Axis::Z => self.z = boundary,
+        }
+        self
+    }
+
+    /// Get the boundary of a single axis.
+    pub fn boundary(&self, axis: Axis) -> Boundary {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+
+    /// Find the coordinates reached by moving from a block by the offset,
+    /// within a structure with the given layouts.
+    /// The layer is found first, then the row within it, then the block within that row,
+    /// so that each axis is resolved against the lengths of a ragged structure at that point.
+    /// Returns None if the position lies outside of a bounded axis.
+    /// ```
+    /// use blok::{ Layout, layout };
+    /// use blok::order::{ Axis, Boundary, Topology };
+    ///
+    /// let layouts = vec![layout![3, 2]; 2];
+    /// let topology = Topology::bounded().with(Axis::Y, Boundary::Toroidal);
+    /// assert_eq!(topology.resolve(&layouts, (0, 0, 2), (0, 0, 1)), Some((0, 0, 0)));
+    /// assert_eq!(topology.resolve(&layouts, (0, 0, 2), (0, 1, 0)), Some((0, 1, 0)));
+    /// assert_eq!(topology.resolve(&layouts, (1, 0, 0), (1, 0, 0)), None);
+    /// ```
+    pub fn resolve(&self, layouts: &[Layout], coord: Coord, offset: Offset) -> Option<Coord> {
+        let (l, r, b) = coord;
+        let (dl, dr, db) = offset;
+
+        let l = self.z.resolve(l as isize + dl, layouts.len())?;
+        let layout = &layouts[l];
+        let r = self.x.resolve(r as isize + dr, layout.len())?;
+        let b = self.y.resolve(b as isize + db, layout[r])?;
+        Some((l, r, b))
+    }
+
+    /// Find the distinct blocks reached from a block by each of the offsets,
+    /// leaving out the block itself.
+    /// Returns an empty list if the block is not present.
+    pub fn neighbors(&self, layouts: &[Layout], coord: Coord, offsets: &[Offset]) -> Vec<Coord> {
+        let (l, r, b) = coord;
+        let present = layouts.get(l)
+            .and_then(|layout| layout.get(r))
+            .is_some_and(|&len| b < len);
+
+        let mut neighbors = Vec::new();
+        if !present {
+            return neighbors
+        }
+
+        for &offset in offsets {
+            if let Some(found) = self.resolve(layouts, coord, offset) {
+                if found != coord && !neighbors.contains(&found) {
+                    neighbors.push(found);
+                }
+            }
+        }
+        neighbors
+    }
+
+}
+
+/// Neighbor queries for stacks:
+impl<B: Block> Stack<B> {
+
+    /// Find the distinct blocks reached from the block at (l, r, b) by each of the offsets,
+    /// under the given topology, leaving out the block itself.
+    /// Returns an empty list if the block is not present.
+    /// Use `FACE_OFFSETS` for the blocks sharing a face.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// use blok::order::{ Topology, FACE_OFFSETS };
+    /// # use blok::Block;
+    /// # #[derive(Default, Clone)]
+    /// # struct MyBlock;
+    /// # impl Block for MyBlock {
+    /// #     type CreationInstructions = ();
+    /// #     fn create(_: &()) -> Self { MyBlock }
+    /// #     fn void() -> Self { MyBlock }
+    /// #     fn is_void(&self) -> bool { false }
+    /// # }
+    ///
+    /// let mut stack = Stack::<MyBlock>::new();
+    /// stack.populate(vec![layout![3, 3, 3]; 3], &());
+    ///
+    /// let corner = stack.neighbors(0, 0, 0, &FACE_OFFSETS, &Topology::bounded());
+    /// assert_eq!(corner, vec![(1, 0, 0), (0, 1, 0), (0, 0, 1)]);
+    ///
+    /// // Layer 0 neighbors the top layer, and row 0 the last row.
+    /// let corner = stack.neighbors(0, 0, 0, &FACE_OFFSETS, &Topology::toroidal());
+    /// assert_eq!(corner.len(), 6);
+    /// assert!(corner.contains(&(2, 0, 0)) && corner.contains(&(0, 2, 0)));
+    /// ```
+    pub fn neighbors(
+        &self,
+        l: usize,
+        r: usize,
+        b: usize,
+        offsets: &[Offset],
+        topology: &Topology
+    ) -> Vec<Coord> {
+
+        topology.neighbors(self.layouts(), (l, r, b), offsets)
+    }
+
+}
+
+/// Neighbor queries for layers:
+impl<B: Block> Layer<B> {
+
+    /// Find the distinct blocks reached from the block at (r, b) by each of the offsets,
+    /// under the given topology, leaving out the block itself.
+    /// Returns an empty list if the block is not present.
+    /// The layer offset is resolved as though the layer were a stack of one layer.
+    pub fn neighbors(
+        &self,
+        r: usize,
+        b: usize,
+        offsets: &[Offset],
+        topology: &Topology
+    ) -> Vec<Coord> {
+
+        topology.neighbors(&[self.layout().clone()], (0, r, b), offsets)
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+    use crate::layout;
+    use crate::types::stack::test::test_stack;
+
+    /// Test that ragged rows are resolved against their own lengths.
+    #[test] fn ragged_resolve_test() {
+        let stack = test_stack();
+        let topology = Topology::toroidal();
+
+        // The single block of row 0 neighbors itself along y, so it is left out.
+        let found = stack.neighbors(1, 0, 0, &FACE_OFFSETS, &topology);
+        assert_eq!(found, vec![(0, 0, 0), (2, 0, 0), (1, 1, 0)]);
+
+        let found = stack.neighbors(1, 1, 1, &[(0, -1, 0)], &Topology::bounded());
+        assert!(found.is_empty());
+        assert!(stack.neighbors(1, 0, 1, &FACE_OFFSETS, &topology).is_empty());
+    }
+
+    /// Test reflective boundaries on a layer.
+    #[test] fn reflective_layer_test() {
+        let mut layer = Layer::<u8>::default();
+        layer.populate(layout![4], &1);
+        let topology = Topology::reflective();
+
+        assert_eq!(layer.neighbors(0, 0, &[(0, 0, -1), (0, 0, -2)], &topology), vec![(0, 0, 1)]);
+        assert_eq!(layer.neighbors(0, 3, &[(0, 0, 2)], &topology), vec![(0, 0, 2)]);
+    }
+
+}
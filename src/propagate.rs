@@ -0,0 +1,225 @@
+
+use crate::{ Node, Stack, Coord };
+use crate::connect::ConnectionMap;
+use crate::connect::plan::find_index;
+use crate::connect::cascade::index_structure;
+
+/// Implement Propagate for a node type to pass signals between blocks
+/// along the connections recorded in a map.
+/// Each step is synchronous: every signal is emitted from the state blocks had
+/// before the step began, then delivered, then blocks update.
+pub trait Propagate: Node {
+
+    /// Define the value passed along a connection.
+    type Signal;
+
+    /// Produce the signal sent along a connection with the given instructions,
+    /// or None to stay silent.
+    fn emit(&self, instructions: &Self::ConnectionInstructions) -> Option<Self::Signal>;
+
+    /// Take in a signal sent from the block at the given coordinates.
+    fn receive(&mut self, signal: Self::Signal, from: Coord);
+
+    /// Apply the signals received during the step.
+    /// Returns true if the block changed, to tell whether the network has settled.
+    fn update(&mut self) -> bool;
+
+}
+
+/// Methods for running signals through a stack:
+impl<N: Propagate> Stack<N> {
+
+    /// Emit signals from every block, deliver them, and update every block.
+    /// Returns the number of blocks that changed.
+    /// Connections to coordinates outside of the stack are ignored.
+    pub fn propagate_step(&mut self, map: &ConnectionMap<N::ConnectionInstructions>) -> usize {
+        let coords: Vec<Coord> = map.coords().into_iter().collect();
+        self.emit_helper(map, &coords);
+        self.blocks_mut().iter_mut()
+            .map(|block| block.update())
+            .filter(|changed| *changed)
+            .count()
+    }
+
+    /// Run steps until no block changes, for recurrent networks.
+    /// Returns the number of steps taken, including the step where the network settled,
+    /// or an error if it has not settled within the maximum number of steps.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// use blok::connect::ConnectionMap;
+    /// use blok::propagate::Propagate;
+    /// # use blok::{ Block, Node, Coord };
+    /// # #[derive(Default, Clone)]
+    /// # struct Cell { on: bool, lit: bool }
+    /// # impl Block for Cell {
+    /// #     type CreationInstructions = bool;
+    /// #     fn create(on: &bool) -> Self { Cell { on: *on, lit: false } }
+    /// #     fn void() -> Self { Self::default() }
+    /// #     fn is_void(&self) -> bool { false }
+    /// # }
+    /// # impl Node for Cell {
+    /// #     type ConnectionInstructions = ();
+    /// #     fn connect(&mut self, _: &mut Self, _: &()) {}
+    /// # }
+    /// impl Propagate for Cell {
+    ///     type Signal = ();
+    ///     fn emit(&self, _: &()) -> Option<()> { self.on.then_some(()) }
+    ///     fn receive(&mut self, _: (), _: Coord) { self.lit = true }
+    ///     fn update(&mut self) -> bool {
+    ///         let changed = self.lit && !self.on;
+    ///         self.on |= self.lit;
+    ///         self.lit = false;
+    ///         changed
+    ///     }
+    /// }
+    ///
+    /// // Light spreads along a chain of three cells.
+    /// let mut stack = Stack::<Cell>::new();
+    /// stack.populate(vec![layout![3]], &false);
+    /// stack.get_block_mut(0, 0, 0).unwrap().on = true;
+    ///
+    /// let mut map = ConnectionMap::new();
+    /// map.connect((0, 0, 0), (0, 0, 1), ()).connect((0, 0, 1), (0, 0, 2), ());
+    ///
+    /// assert_eq!(stack.propagate_until_settled(&map, 10).unwrap(), 3);
+    /// assert!(stack.blocks().iter().all(|cell| cell.on));
+    /// ```
+    pub fn propagate_until_settled(
+        &mut self,
+        map: &ConnectionMap<N::ConnectionInstructions>,
+        max_steps: usize
+    ) -> anyhow::Result<usize> {
+
+        for step in 1..=max_steps {
+            if self.propagate_step(map) == 0 {
+                return Ok(step)
+            }
+        }
+
+        Err(anyhow::anyhow!("Network did not settle within {} steps", max_steps))
+    }
+
+    /// Make a feed-forward pass through the stack in layer order.
+    /// Each layer is updated with the signals it has received so far,
+    /// then emits signals from its new state before the next layer is updated.
+    /// Signals sent back to earlier layers are delivered, but are not applied until the next pass.
+    /// Returns the number of blocks that changed.
+    pub fn propagate_forward(&mut self, map: &ConnectionMap<N::ConnectionInstructions>) -> usize {
+        let mut changed = 0;
+        for (l, layer) in index_structure(self.layouts()).into_iter().enumerate() {
+            let mut coords = Vec::new();
+            for (r, row) in layer.into_iter().enumerate() {
+                for (b, index) in row.into_iter().enumerate() {
+                    if self.blocks_mut()[index].update() { changed += 1 }
+                    coords.push((l, r, b));
+                }
+            }
+            self.emit_helper(map, &coords);
+        }
+        changed
+    }
+
+    /// Emit signals from the blocks at the given coordinates,
+    /// delivering them only after every signal has been emitted.
+    fn emit_helper(&mut self, map: &ConnectionMap<N::ConnectionInstructions>, coords: &[Coord]) {
+        let indexes = index_structure(self.layouts());
+        let blocks = self.blocks_mut();
+
+        let mut signals = Vec::new();
+        for &from in coords {
+            let Some(i) = find_index(&indexes, from) else { continue };
+            for (to, instructions) in map.outgoing(from) {
+                let Some(j) = find_index(&indexes, to) else { continue };
+                if let Some(signal) = blocks[i].emit(instructions) {
+                    signals.push((from, j, signal));
+                }
+            }
+        }
+
+        for (from, j, signal) in signals {
+            blocks[j].receive(signal, from);
+        }
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use super::*;
+    use crate::{ Block, Layout, layout };
+
+    /// Integer neuron that sums weighted inputs.
+    #[derive(Debug, Default, Clone)]
+    struct Neuron { value: i32, input: i32 }
+
+    impl Block for Neuron {
+        type CreationInstructions = i32;
+        fn create(value: &i32) -> Self { Neuron { value: *value, input: 0 } }
+        fn void() -> Self { Self::default() }
+        fn is_void(&self) -> bool { false }
+    }
+
+    impl Node for Neuron {
+        type ConnectionInstructions = i32;
+        fn connect(&mut self, _: &mut Self, _: &i32) {}
+    }
+
+    impl Propagate for Neuron {
+        type Signal = i32;
+        fn emit(&self, weight: &i32) -> Option<i32> { Some(self.value * weight) }
+        fn receive(&mut self, signal: i32, _: Coord) { self.input += signal }
+        fn update(&mut self) -> bool {
+            let changed = self.value != self.input;
+            self.value = self.input;
+            self.input = 0;
+            changed
+        }
+    }
+
+    /// Test a feed-forward pass through three layers.
+    #[test] fn propagate_forward_test() {
+        let mut stack = Stack::<Neuron>::new();
+        stack.populate(vec![layout![2], layout![2], layout![1]], &0);
+
+        let mut map = ConnectionMap::new();
+        map.connect((0, 0, 0), (1, 0, 0), 1)
+            .connect((0, 0, 1), (1, 0, 0), 2)
+            .connect((0, 0, 1), (1, 0, 1), -1)
+            .connect((1, 0, 0), (2, 0, 0), 3)
+            .connect((1, 0, 1), (2, 0, 0), 1);
+
+        // Inputs are given as received signals, so that the first layer applies them.
+        stack.get_block_mut(0, 0, 0).unwrap().input = 2;
+        stack.get_block_mut(0, 0, 1).unwrap().input = 5;
+        stack.propagate_forward(&map);
+
+        let values: Vec<i32> = stack.blocks().iter().map(|n| n.value).collect();
+        assert_eq!(values, vec![2, 5, 12, -5, 31]);
+    }
+
+    /// Test synchronous steps over a recurrent network.
+    #[test] fn propagate_step_test() {
+        let mut stack = Stack::<Neuron>::new();
+        stack.populate(vec![layout![2]], &0);
+        stack.get_block_mut(0, 0, 0).unwrap().value = 1;
+
+        // Two blocks swap values each step.
+        let mut map = ConnectionMap::new();
+        map.connect((0, 0, 0), (0, 0, 1), 1)
+            .connect((0, 0, 1), (0, 0, 0), 1);
+
+        assert_eq!(stack.propagate_step(&map), 2);
+        let values: Vec<i32> = stack.blocks().iter().map(|n| n.value).collect();
+        assert_eq!(values, vec![0, 1]);
+        assert!(stack.propagate_until_settled(&map, 5).is_err());
+
+        // Decaying connections settle once every value reaches zero.
+        map.connect((0, 0, 0), (0, 0, 1), 0);
+        assert_eq!(stack.propagate_until_settled(&map, 5).unwrap(), 2);
+    }
+
+}
+
@@ -7,7 +7,7 @@ impl<B: Block> Layer<B> {
     /// Add a new block to the last row in the layer.
     pub fn add_block(&mut self, block: B) -> &mut Self {
 
-        if self.layout().len() == 0 { 
+        if self.layout().is_empty() { 
             self.new_row(); 
         }
 
@@ -21,7 +21,7 @@ impl<B: Block> Layer<B> {
     /// Add a collection of blocks to the last row in the layer.
     pub fn add_blocks(&mut self, mut blocks: Vec<B>) -> &mut Self {
 
-        if self.layout().len() == 0 { 
+        if self.layout().is_empty() { 
             self.new_row(); 
         }
 
@@ -115,7 +115,7 @@ impl<B: Block> Layer<B> {
 /*  UNIT TESTS  */
 #[cfg(test)] mod test {
 
-    use crate::{ Block, Layer };
+    use crate::Block;
     use crate::block::test::TestBlock;
     use crate::types::layer::test::test_layer;
 
@@ -28,7 +28,7 @@ pub(super) fn previous_available_row_recursion_helper<B: Block>(
     use crate::block::{ Block, test::TestBlock };
     use crate::types::layer::test::test_layer;
 
-    /// 
+    //
     #[test] fn previous_available_row_recursion_test() {
 
         let mut layer = test_layer();
@@ -57,11 +57,12 @@ impl<B: Block> Layer<B> {
 /*  UNIT TESTS  */
 #[cfg(test)] mod test {
 
-    use crate::{ Block, Layer, Row };
+    use crate::{ Block, Row };
     use crate::block::test::TestBlock;
     use crate::types::layer::test::test_layer;
 
     /// Test adding row(s) to a layer.
+    #[allow(unused_variables)]
     #[test] fn add_row_test() {
 
         let mut layer = test_layer();
@@ -97,6 +98,7 @@ impl<B: Block> Layer<B> {
     }
 
     /// Test inserting row(s) to a layer.
+    #[allow(unused_variables)]
     #[test] fn insert_row_test() {
 
         let mut layer = test_layer();
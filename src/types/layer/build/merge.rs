@@ -0,0 +1,80 @@
+
+use crate::{ Block, Layer };
+use crate::order::BlendMode;
+use crate::order::blend::paste_block;
+
+/// Functions for combining layers:
+impl<B: Block> Layer<B> {
+
+    /// Paste the other layer into this one, with its first block at the given (r, b) offset,
+    /// combining blocks that land on existing ones by the blend mode.
+    /// Rows and row positions that do not exist yet are grown with voids.
+    /// ```
+    /// use blok::{ Layer, Layout, layout };
+    /// use blok::order::BlendMode;
+    ///
+    /// let mut layer = Layer::<u8>::default();
+    /// layer.populate(layout![3], &1);
+    /// let mut stamp = Layer::<u8>::default();
+    /// stamp.populate(layout![2, 2], &2);
+    ///
+    /// layer.paste(&stamp, (0, 2), &BlendMode::Overwrite);
+    /// assert_eq!(layer.layout(), &layout![4, 4]);
+    /// assert_eq!(layer.blocks(), &vec![1, 1, 2, 2, 0, 0, 2, 2]);
+    /// ```
+    pub fn paste(
+        &mut self,
+        other: &Self,
+        offset: (usize, usize),
+        mode: &BlendMode<B>
+    ) -> &mut Self {
+
+        let mut rows = self.clone_into_blocks();
+        for (r, row) in other.clone_into_blocks().into_iter().enumerate() {
+            let r = r + offset.0;
+            if rows.len() <= r {
+                rows.resize(r + 1, Vec::new());
+            }
+
+            for (b, block) in row.iter().enumerate() {
+                paste_block(&mut rows[r], b + offset.1, block, mode);
+            }
+        }
+
+        self.set_from_blocks(rows);
+        self
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::{ Layer, Layout, layout };
+    use crate::order::BlendMode;
+
+    /// Test each blend mode on overlapping and grown positions.
+    #[test] fn paste_blend_test() {
+        let mut layer = Layer::<u8>::default();
+        layer.populate(layout![3], &1);
+        layer.get_row_mut(0).unwrap().into_iter().take(1).for_each(|b| *b = 0);
+        let mut stamp = Layer::<u8>::default();
+        stamp.populate(layout![3], &2);
+
+        let mut filled = layer.clone();
+        filled.paste(&stamp, (0, 0), &BlendMode::FillVoids);
+        assert_eq!(filled.blocks(), &vec![2, 1, 1]);
+
+        let mut kept = layer.clone();
+        kept.paste(&stamp, (0, 1), &BlendMode::KeepExisting);
+        assert_eq!(kept.blocks(), &vec![0, 1, 1, 2]);
+
+        let mut merged = layer.clone();
+        merged.paste(&stamp, (1, 1), &BlendMode::custom(|a: &u8, b: &u8| a + b));
+        assert_eq!(merged.layout(), &layout![3, 4]);
+        assert_eq!(merged.blocks()[3..], [0, 2, 2, 2]);
+    }
+
+}
@@ -8,9 +8,9 @@ pub mod populate;
 /// Methods for using blocks that represent empty space.
 pub mod void;
 
-// Methods for changing a row's organization by manipulating clones.
-// pub mod transform;
+/// Methods for changing a layer's organization by manipulating clones.
+pub mod transform;
 
-// Methods for combining rows.
-// pub mod merge;
+/// Methods for combining layers.
+pub mod merge;
 
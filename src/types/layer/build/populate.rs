@@ -51,7 +51,7 @@ impl<B: Block> Layer<B> {
     use crate::{ Block, Layer, Layout, layout };
     use crate::block::test::TestBlock;
     
-    ///
+    //
     #[test] fn populate_test() {
 
         let layout = layout![2, 2];
@@ -1,5 +1,7 @@
 
 use crate::{ Block, Layer };
+use crate::order::{ Axis, AxisTransform };
+use crate::order::axis::{ missing_axis, roll_slice };
 
 /// Layer transformation functions:
 impl<B: Block> Layer<B> {
@@ -63,9 +65,11 @@ impl<B: Block> Layer<B> {
         self.set_from_blocks(s1);
     }
 
-    /// Split a layer into two at the given row number. Leaves the original in place.
+    /// Split a layer into two at the given row number. Leaves the original in place,
+    /// and returns the remainder, which is empty if the row number is past the end of the layer.
     pub fn split_x(&mut self, split: usize) -> Self {
         let mut original = self.clone_into_blocks();
+        let split = split.min(original.len());
         let remainder = original.split_off(split);
 
         self.set_from_blocks(original);
@@ -114,7 +118,7 @@ impl<B: Block> Layer<B> {
         let other = other.clone_into_blocks();
 
         let riffled: Vec<Vec<B>> = rows.into_iter()
-            .zip(other.into_iter())
+            .zip(other)
             .flat_map(|(r, o)| vec![r, o])
             .collect();
 
@@ -128,10 +132,10 @@ impl<B: Block> Layer<B> {
         let other = other.clone_into_blocks();
 
         let riffled: Vec<Vec<B>> = rows.into_iter()
-            .zip(other.into_iter())
+            .zip(other)
             .map(|(r, o)| {
                 let r: Vec<B> = r.into_iter()
-                    .zip(o.into_iter())
+                    .zip(o)
                     .flat_map(|(rr, oo)| vec![rr, oo])
                     .collect();
                 r
@@ -141,13 +145,241 @@ impl<B: Block> Layer<B> {
         self.set_from_blocks(riffled);
     }
 
+    /// Move the rows n places, toward the end if n is positive,
+    /// wrapping rows that pass one end around to the other.
+    pub fn roll_x(&mut self, n: isize) {
+        let mut rows = self.clone_into_blocks();
+        roll_slice(&mut rows, n);
+
+        self.set_from_blocks(rows);
+    }
+
+    /// Move the blocks within each row n places, toward the end if n is positive,
+    /// wrapping blocks that pass one end of their row around to the other.
+    /// Rows of different lengths are rolled independently.
+    pub fn roll_y(&mut self, n: isize) {
+        let mut rows = self.clone_into_blocks();
+        rows.iter_mut()
+            .for_each(|row| { roll_slice(row, n); });
+
+        self.set_from_blocks(rows);
+    }
+
+    /// Move the rows n places, toward the end if n is positive.
+    /// Rows that pass one end are dropped, and replaced with rows of voids at the other.
+    pub fn shift_x(&mut self, n: isize) {
+        let mut rows = self.clone_into_blocks();
+        let wrapped = roll_slice(&mut rows, n);
+        rows[wrapped].iter_mut()
+            .for_each(|row| row.fill(B::void()));
+
+        self.set_from_blocks(rows);
+    }
+
+    /// Move the blocks within each row n places, toward the end if n is positive.
+    /// Blocks that pass one end of their row are dropped, and replaced with voids at the other.
+    pub fn shift_y(&mut self, n: isize) {
+        let mut rows = self.clone_into_blocks();
+        for row in rows.iter_mut() {
+            let wrapped = roll_slice(row, n);
+            row[wrapped].fill(B::void());
+        }
+
+        self.set_from_blocks(rows);
+    }
+
+    /// Repeat the layer the given number of times along each axis, as (rows, blocks).
+    /// A count of 0 leaves the layer empty.
+    pub fn tile(&mut self, counts: (usize, usize)) {
+        self.tile_with(counts, |_, _| {});
+    }
+
+    /// Repeat the layer the given number of times along each axis,
+    /// flipping every other copy along each axis, so that neighboring copies mirror each other.
+    /// ```
+    /// use blok::{ Layer, Layout, layout };
+    ///
+    /// let mut layer = Layer::<u8>::default();
+    /// layer.populate(layout![2], &1);
+    /// layer.get_row_mut(0).unwrap().into_iter().take(1).for_each(|b| *b = 2);
+    ///
+    /// layer.tile_mirrored((2, 2));
+    /// assert_eq!(layer.layout(), &layout![4, 4]);
+    /// assert_eq!(layer.blocks()[..4], [2, 1, 1, 2]);
+    /// ```
+    pub fn tile_mirrored(&mut self, counts: (usize, usize)) {
+        self.tile_with(counts, |(j, k), tile| {
+            if j % 2 == 1 { tile.flip_x() }
+            if k % 2 == 1 { tile.flip_y() }
+        });
+    }
+
+    /// Repeat the layer the given number of times along each axis,
+    /// passing each copy to the closure with its tile index before it is placed.
+    pub fn tile_with(
+        &mut self,
+        counts: (usize, usize),
+        mut f: impl FnMut((usize, usize), &mut Self)
+    ) {
+        let mut tiled = Self::default();
+        for j in 0..counts.0 {
+            let mut rows = Self::default();
+            for k in 0..counts.1 {
+                let mut tile = self.clone();
+                f((j, k), &mut tile);
+                if k == 0 { rows = tile } else { rows.stitch_y(&mut tile) }
+            }
+            tiled.stitch_x(&mut rows);
+        }
+
+        *self = tiled;
+    }
+
+}
+
+
+/// Transformations along an axis chosen at runtime.
+/// Layers do not have a Z axis.
+impl<B: Block> AxisTransform for Layer<B> {
+
+    fn split(&mut self, axis: Axis, at: usize) -> anyhow::Result<Self> {
+        match axis {
+            Axis::X => Ok(self.split_x(at)),
+            Axis::Y => Ok(self.split_y(at)),
+            Axis::Z => Err(missing_axis("Layer", axis)),
+        }
+    }
+
+    fn flip(&mut self, axis: Axis) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.flip_x(),
+            Axis::Y => self.flip_y(),
+            Axis::Z => return Err(missing_axis("Layer", axis)),
+        }
+        Ok(self)
+    }
+
+    fn stitch(&mut self, axis: Axis, other: &mut Self) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.stitch_x(other),
+            Axis::Y => self.stitch_y(other),
+            Axis::Z => return Err(missing_axis("Layer", axis)),
+        }
+        Ok(self)
+    }
+
+    fn mirror(&mut self, axis: Axis) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.mirror_x(),
+            Axis::Y => self.mirror_y(),
+            Axis::Z => return Err(missing_axis("Layer", axis)),
+        }
+        Ok(self)
+    }
+
+    fn riffle(&mut self, axis: Axis, other: &mut Self) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.riffle_x(other),
+            Axis::Y => self.riffle_y(other),
+            Axis::Z => return Err(missing_axis("Layer", axis)),
+        }
+        Ok(self)
+    }
+
+    fn offset(&mut self, axis: Axis, n: usize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => Ok(self.offset_x(n)),
+            Axis::Y => Ok(self.offset_y(n)),
+            Axis::Z => Err(missing_axis("Layer", axis)),
+        }
+    }
+
+    fn pad(&mut self, axis: Axis, n: usize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => Ok(self.pad_x(n)),
+            Axis::Y => Ok(self.pad_y(n)),
+            Axis::Z => Err(missing_axis("Layer", axis)),
+        }
+    }
+
+    fn roll(&mut self, axis: Axis, n: isize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.roll_x(n),
+            Axis::Y => self.roll_y(n),
+            Axis::Z => return Err(missing_axis("Layer", axis)),
+        }
+        Ok(self)
+    }
+
+    fn shift(&mut self, axis: Axis, n: isize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.shift_x(n),
+            Axis::Y => self.shift_y(n),
+            Axis::Z => return Err(missing_axis("Layer", axis)),
+        }
+        Ok(self)
+    }
+
 }
 
 
 
 /*  UNIT TESTS  */
 #[cfg(test)] mod test {
-    //
+
+    use crate::{ Layout, layout };
+    use crate::order::{ Axis, AxisTransform };
+    use crate::types::layer::test::test_layer;
+
+    /// Test that axis transforms match the named transforms, and reject the Z axis.
+    #[test] fn axis_transform_test() {
+        let mut layer = test_layer();
+        let mut named = test_layer();
+        layer.mirror(Axis::X).unwrap();
+        named.mirror_x();
+        assert_eq!(layer.layout(), named.layout());
+
+        let split = layer.split(Axis::Y, 1).unwrap();
+        assert_eq!(split.layout(), &layout![1, 1]);
+        let split = layer.split(Axis::X, 10).unwrap();
+        assert!(split.layout().is_empty());
+        assert_eq!(layer.layout().len(), 4);
+        assert!(layer.flip(Axis::Z).is_err());
+        assert!(layer.offset(Axis::Z, 1).is_err());
+    }
+
+    /// Test that tiles are placed in order, and that a count of 0 empties the layer.
+    #[test] fn tile_test() {
+        let mut layer = test_layer();
+        layer.tile_with((2, 3), |(j, k), tile| {
+            tile.blocks_mut().iter_mut().for_each(|b| b.id = format!("{j}{k}"));
+        });
+        assert_eq!(layer.layout(), &layout![3, 6, 3, 6]);
+        assert_eq!(layer.blocks()[2].id, "02");
+        assert_eq!(layer.blocks()[9].id, "10");
+
+        layer.tile((0, 1));
+        assert!(layer.layout().is_empty());
+    }
+
+    /// Test that rows of different lengths roll independently, and that shifts leave voids.
+    #[test] fn roll_shift_test() {
+        let mut layer = test_layer();
+        for (i, block) in layer.blocks_mut().iter_mut().enumerate() {
+            block.id = i.to_string();
+        }
+
+        let mut rolled = layer.clone();
+        rolled.roll(Axis::Y, 1).unwrap();
+        let ids: Vec<&str> = rolled.blocks().iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["0", "2", "1"]);
+
+        layer.shift(Axis::X, -1).unwrap();
+        assert_eq!(layer.layout(), &layout![2, 1]);
+        let ids: Vec<&str> = layer.blocks().iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", ""]);
+    }
+
 }
 
 
@@ -1,5 +1,8 @@
 
-use crate::{ Block, Layer };
+use crate::{ Block, Layer, Coord };
+use crate::order::Justify;
+use crate::order::justify::justify_blocks;
+use crate::order::coord::flat_coords;
 
 /// Functions for modeling gaps and empty space:
 impl<B: Block> Layer<B> {
@@ -143,7 +146,7 @@ impl<B: Block> Layer<B> {
 
         for block in self.blocks_mut().iter_mut() {
             if block.is_void() {
-                *block = B::create(&instructions)
+                *block = B::create(instructions)
             }
         }
         self
@@ -160,7 +163,7 @@ impl<B: Block> Layer<B> {
         self
     }
 
-    /// Remove all void blocks from the matrix.
+    /// Remove all void blocks from the matrix, then remove the rows left empty.
     pub fn compress(&mut self) -> &mut Self {
 
         let mut rows = self.clone_into_blocks();
@@ -170,12 +173,147 @@ impl<B: Block> Layer<B> {
                     .filter(|block| !block.is_void())
                     .collect()
             )
+            .filter(|row: &Vec<B>| !row.is_empty())
             .collect();
 
-        // TODO Remove empty rows
+        self.set_from_blocks(rows);
+
+        self
+    }
+
+    /// Move the non-void blocks of every row to the start, end, or center,
+    /// padding each row with voids to the length of the longest row.
+    /// ```
+    /// use blok::{ Layer, Layout, layout };
+    /// use blok::order::Justify;
+    ///
+    /// let mut layer = Layer::<u8>::default();
+    /// layer.populate(layout![1, 3], &1);
+    /// layer.justify(Justify::End);
+    ///
+    /// assert_eq!(layer.layout(), &layout![3, 3]);
+    /// assert_eq!(layer.blocks(), &vec![0, 0, 1, 1, 1, 1]);
+    /// ```
+    pub fn justify(&mut self, justify: Justify) -> &mut Self {
+
+        let rows = self.clone_into_blocks();
+        let width = rows.iter()
+            .map(|row| row.len())
+            .max()
+            .unwrap_or(0);
+
+        let rows = rows.into_iter()
+            .map(|row| justify_blocks(row, width, justify))
+            .collect();
+
+        self.set_from_blocks(rows);
+        self
+    }
+
+    /// Move the non-void blocks of a single row to the start, end, or center of it,
+    /// keeping the length of the row.
+    pub fn justify_row(
+        &mut self,
+        r: usize,
+        justify: Justify
+    ) -> anyhow::Result<&mut Self> {
+
+        if r >= self.layout().len() {
+            return Err(anyhow::anyhow!("Row {} could not be found", r))
+        }
+
+        let mut rows = self.clone_into_blocks();
+        let row = std::mem::take(&mut rows[r]);
+        let width = row.len();
+        rows[r] = justify_blocks(row, width, justify);
+
+        self.set_from_blocks(rows);
+        Ok(self)
+    }
+
+    /// Find the smallest box holding every non-void block, as its first and last corners,
+    /// given as (0, r, b). Returns None if the layer has no non-void blocks.
+    pub fn bounding_box(&self) -> Option<(Coord, Coord)> {
+        flat_coords(&[self.layout().clone()])
+            .into_iter()
+            .zip(self.blocks())
+            .filter(|(_, block)| !block.is_void())
+            .fold(None, |bounds, (c, _)| match bounds {
+                None => Some((c, c)),
+                Some((min, max)) => Some((
+                    (0, c.1.min(min.1), c.2.min(min.2)),
+                    (0, c.1.max(max.1), c.2.max(max.2)),
+                )),
+            })
+    }
+
+    /// Keep only the blocks within the box between the given corners, inclusive,
+    /// moving the first corner to (0, 0, 0). The layer component of each corner is ignored.
+    /// Rows that end before the box are kept as empty rows.
+    /// Returns an error if the first corner is past the second.
+    pub fn crop(&mut self, min: Coord, max: Coord) -> anyhow::Result<&mut Self> {
+        if min.1 > max.1 || min.2 > max.2 {
+            return Err(anyhow::anyhow!("Corner {:?} is past corner {:?}", min, max))
+        }
+
+        let rows = self.clone_into_blocks()
+            .into_iter()
+            .skip(min.1)
+            .take(max.1 - min.1 + 1)
+            .map(|row| {
+                row.into_iter()
+                    .skip(min.2)
+                    .take(max.2 - min.2 + 1)
+                    .collect()
+            })
+            .collect();
 
         self.set_from_blocks(rows);
+        Ok(self)
+    }
+
+    /// Copy the blocks within the box between the given corners, inclusive,
+    /// into a new layer, leaving this one in place.
+    pub fn cropped(&self, min: Coord, max: Coord) -> anyhow::Result<Self> {
+        let mut layer = self.clone();
+        layer.crop(min, max)?;
+        Ok(layer)
+    }
+
+    /// Remove the void rows before and after the non-void blocks,
+    /// the voids before the first non-void column, and the voids at the end of each row.
+    /// Undoes `offset_*`, `pad_*` and `realize_voids`. A layer of voids is left empty.
+    /// ```
+    /// use blok::{ Layer, Layout, layout };
+    ///
+    /// let mut layer = Layer::<u8>::default();
+    /// layer.populate(layout![2, 1], &1);
+    /// layer.offset_y(2);
+    /// layer.pad_x(1);
+    /// layer.realize_voids();
+    /// assert_eq!(layer.bounding_box(), Some(((0, 0, 2), (0, 1, 3))));
+    ///
+    /// layer.trim();
+    /// assert_eq!(layer.layout(), &layout![2, 1]);
+    /// ```
+    pub fn trim(&mut self) -> &mut Self {
+        let Some((min, max)) = self.bounding_box() else {
+            self.set_from_blocks(Vec::new());
+            return self
+        };
+
+        self.crop(min, max)
+            .expect("Bounding box corners are in order");
+
+        let rows = self.clone_into_blocks()
+            .into_iter()
+            .map(|mut row| {
+                while row.last().is_some_and(|block| block.is_void()) { row.pop(); }
+                row
+            })
+            .collect();
 
+        self.set_from_blocks(rows);
         self
     }
 
@@ -187,7 +325,8 @@ impl<B: Block> Layer<B> {
 
     // TODO: Need assert statements
 
-    use crate::{ Block, Layer };
+    use crate::{ Layer, Layout, layout };
+    use crate::order::Justify;
     use crate::types::layer::test::test_layer;
 
     #[test] fn offset_test() {
@@ -240,9 +379,37 @@ impl<B: Block> Layer<B> {
 
         let mut layer = test_layer();
         layer.realize_voids();
+        layer.offset_x(1);
         layer.compress();
 
-        // assert 3 total, no voids
+        assert_eq!(layer.layout(), &layout![1, 2]);
+    }
+
+    #[test] fn crop_test() {
+
+        let mut layer = test_layer();
+        layer.crop((0, 1, 1), (0, 1, 4)).unwrap();
+        assert_eq!(layer.layout(), &layout![1]);
+        assert_eq!(layer.blocks()[0].id, "2");
+
+        assert!(layer.crop((0, 1, 0), (0, 0, 0)).is_err());
+        assert_eq!(Layer::<u8>::default().trim().layout(), &layout![]);
+    }
+
+    #[test] fn justify_test() {
+
+        let mut layer = Layer::<u8>::default();
+        layer.populate(layout![2, 5], &1);
+        layer.justify_row(1, Justify::Center).unwrap();
+        assert_eq!(layer.blocks(), &vec![1, 1, 1, 1, 1, 1, 1]);
+
+        layer.get_row_mut(1).unwrap().into_iter().take(3).for_each(|b| *b = 0);
+        layer.justify_row(1, Justify::Center).unwrap();
+        assert_eq!(layer.blocks()[2..], [0, 1, 1, 0, 0]);
+
+        layer.justify(Justify::Start);
+        assert_eq!(layer.layout(), &layout![5, 5]);
+        assert!(layer.justify_row(2, Justify::End).is_err());
     }
 
 }
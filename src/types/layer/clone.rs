@@ -73,7 +73,7 @@ impl<B: Block> Layer<B> {
     use crate::{ layout, Layout, Layer, Block };
     use crate::block::test::TestBlock;
 
-    ///
+    //
     #[test] fn clone_layer_into_blocks_test() {
         let mut layer = Layer::<TestBlock>::new();
         let layout = layout![1, 2];
@@ -93,7 +93,7 @@ impl<B: Block> Layer<B> {
         assert_eq!(other_layer.blocks.len(), 3);
     }
 
-    ///
+    //
     #[test] #[should_panic] fn bad_clone_layer_into_blocks_test() {
         let mut layer = Layer::<TestBlock>::new();
         let layout = layout![1, 2];
@@ -106,7 +106,7 @@ impl<B: Block> Layer<B> {
         let _cloned_blocks = layer.clone_into_blocks();
     }
 
-    ///
+    //
     #[test] fn clone_layer_into_rows_test() {
         let mut layer = Layer::<TestBlock>::new();
         let layout = layout![1, 2];
@@ -126,7 +126,7 @@ impl<B: Block> Layer<B> {
         assert_eq!(other_layer.blocks.len(), 3);
     }
 
-    ///
+    //
     #[test] #[should_panic] fn bad_clone_layer_into_rows_test() {
         let mut layer = Layer::<TestBlock>::new();
         let layout = layout![1, 2];
@@ -64,11 +64,9 @@ impl<B: Block> Layer<B> {
 /*  UNIT TESTS  */
 #[cfg(test)] mod test {
 
-    use super::*;
-    use crate::{ Block, Layer, Layout };
     use crate::types::layer::test::test_layer;
 
-    ///
+    //
     #[test] fn find_block_index_test() {
 
         // Test layer layout is [1, 2]
@@ -82,7 +80,7 @@ impl<B: Block> Layer<B> {
         assert!(bad_index.is_err());
     }
 
-    ///
+    //
     #[test] fn get_block_test() {
 
         // Test layer layout is [1, 2]
@@ -9,8 +9,7 @@ pub(crate) fn range_boundary_check_helper(
     end: usize
 ) -> bool {
 
-    if start > end || end >= total { false } 
-    else { true }
+    !(start > end || end >= total)
 }
 
 /// Helps to organize blocks using the layout, 
@@ -38,7 +37,6 @@ pub(crate) fn collection_organization_helper<T>(
 #[cfg(test)] mod test {
 
     use super::*;
-    use crate::{ Block, Layer, Layout };
     use crate::types::layer::test::test_layer;
 
     /// Test for verifying a range of blocks in a layer.
@@ -58,8 +56,8 @@ pub(crate) fn collection_organization_helper<T>(
             0, 3
         );
 
-        assert_eq!(range_good, true);
-        assert_eq!(range_bad, false);
+        assert!(range_good);
+        assert!(!range_bad);
 
     }
 
@@ -69,7 +67,7 @@ pub(crate) fn collection_organization_helper<T>(
         let layer = test_layer();
 
         let organized_collection = collection_organization_helper(
-            &layer.layout(),
+            layer.layout(),
             layer.blocks().iter().collect()
         );
 
@@ -80,11 +80,9 @@ impl<B: Block> Layer<B> {
 /*  UNIT TESTS  */
 #[cfg(test)] mod test {
 
-    use super::*;
-    use crate::{ Block, Layer, Layout };
     use crate::types::layer::test::test_layer;
 
-    ///
+    //
     #[test] fn get_range_test() {
 
         // Test layer layout is [1, 2]
@@ -104,7 +102,7 @@ impl<B: Block> Layer<B> {
 
     }
 
-    ///
+    //
     #[test] fn get_all_test() {
 
         // Test layer layout is [1, 2]
@@ -71,11 +71,9 @@ impl<B: Block> Layer<B> {
 /*  UNIT TESTS  */
 #[cfg(test)] mod test {
 
-    use super::*;
-    use crate::{ Block, Layer, Layout };
     use crate::types::layer::test::test_layer;
 
-    ///
+    //
     #[test] fn find_row_bounds_test() {
 
         // Test layer layout is [1, 2]
@@ -100,7 +98,7 @@ impl<B: Block> Layer<B> {
         assert!(layer.find_row_bounds(3).is_err());
     }
 
-    ///
+    //
     #[test] fn get_row_test() {
 
         // Test layer layout is [1, 2]
@@ -7,5 +7,6 @@ pub use self::{
     row::Row,
     layer::Layer,
     stack::Stack,
+    stack::view::StackView,
 };
 
@@ -1,7 +1,228 @@
 
 use crate::{ Block, Row };
+use crate::order::{ Axis, AxisTransform };
+use crate::order::axis::{ missing_axis, roll_slice };
 
+/// Row transformation functions:
+/// Rows dereference to their blocks, so `split`, `rotate_left`, `rotate_right` and `chunks`
+/// shadow the slice methods of the same name. Call them on `row.as_slice()` to reach the slice versions.
 impl<B: Block> Row<B> {
-    // TBD
+
+    /// Split a row into two at the given index. Leaves the original in place,
+    /// and returns the remainder, which is empty if the index is past the end of the row.
+    pub fn split(&mut self, at: usize) -> Self {
+        let at = at.min(self.blocks.len());
+        Self { blocks: self.blocks.split_off(at) }
+    }
+
+    /// Reverse the order of blocks in the row.
+    pub fn flip(&mut self) {
+        self.blocks.reverse();
+    }
+
+    /// Add the other row's blocks to the end of this row.
+    pub fn stitch(&mut self, other: &mut Self) {
+        self.blocks.append(&mut other.blocks);
+    }
+
+    /// Stitch a flipped clone (to the end of this row).
+    pub fn mirror(&mut self) {
+        let mut reflection = self.clone();
+        reflection.flip();
+        self.stitch(&mut reflection);
+    }
+
+    /// Merge the other row into this one, by alternating blocks.
+    /// New row will begin with a block originally from "self".
+    pub fn riffle(&mut self, other: &mut Self) {
+        let other = other.blocks.clone();
+
+        self.blocks = std::mem::take(&mut self.blocks).into_iter()
+            .zip(other)
+            .flat_map(|(s, o)| vec![s, o])
+            .collect();
+    }
+
+    /// Cycle the blocks toward the start of the row by the given number of places,
+    /// moving the first blocks to the end.
+    pub fn rotate_left(&mut self, n: usize) {
+        if !self.blocks.is_empty() {
+            let n = n % self.blocks.len();
+            self.blocks.rotate_left(n);
+        }
+    }
+
+    /// Cycle the blocks toward the end of the row by the given number of places,
+    /// moving the last blocks to the start.
+    pub fn rotate_right(&mut self, n: usize) {
+        if !self.blocks.is_empty() {
+            let n = n % self.blocks.len();
+            self.blocks.rotate_right(n);
+        }
+    }
+
+    /// Move every block n places, toward the end if n is positive,
+    /// wrapping blocks that pass one end around to the other.
+    pub fn roll(&mut self, n: isize) {
+        roll_slice(&mut self.blocks, n);
+    }
+
+    /// Move every block n places, toward the end if n is positive.
+    /// Blocks that pass one end are dropped, and replaced with voids at the other.
+    pub fn shift(&mut self, n: isize) {
+        let wrapped = roll_slice(&mut self.blocks, n);
+        self.blocks[wrapped].fill(B::void());
+    }
+
+    /// Clone the row into rows of the given length. The last row may be shorter.
+    /// Returns an empty Vec if the length is 0.
+    pub fn chunks(&self, n: usize) -> Vec<Self> {
+        if n == 0 {
+            return Vec::new()
+        }
+
+        self.blocks.chunks(n)
+            .map(|chunk| Self { blocks: chunk.to_vec() })
+            .collect()
+    }
+
 }
 
+/// Transformations along an axis chosen at runtime.
+/// Rows only have a Y axis.
+impl<B: Block> AxisTransform for Row<B> {
+
+    fn split(&mut self, axis: Axis, at: usize) -> anyhow::Result<Self> {
+        match axis {
+            Axis::Y => Ok(Row::split(self, at)),
+            _ => Err(missing_axis("Row", axis)),
+        }
+    }
+
+    fn flip(&mut self, axis: Axis) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::Y => Row::flip(self),
+            _ => return Err(missing_axis("Row", axis)),
+        }
+        Ok(self)
+    }
+
+    fn stitch(&mut self, axis: Axis, other: &mut Self) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::Y => Row::stitch(self, other),
+            _ => return Err(missing_axis("Row", axis)),
+        }
+        Ok(self)
+    }
+
+    fn mirror(&mut self, axis: Axis) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::Y => Row::mirror(self),
+            _ => return Err(missing_axis("Row", axis)),
+        }
+        Ok(self)
+    }
+
+    fn riffle(&mut self, axis: Axis, other: &mut Self) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::Y => Row::riffle(self, other),
+            _ => return Err(missing_axis("Row", axis)),
+        }
+        Ok(self)
+    }
+
+    fn offset(&mut self, axis: Axis, n: usize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::Y => Ok(Row::offset(self, n)),
+            _ => Err(missing_axis("Row", axis)),
+        }
+    }
+
+    fn pad(&mut self, axis: Axis, n: usize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::Y => Ok(Row::pad(self, n)),
+            _ => Err(missing_axis("Row", axis)),
+        }
+    }
+
+    fn roll(&mut self, axis: Axis, n: isize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::Y => Row::roll(self, n),
+            _ => return Err(missing_axis("Row", axis)),
+        }
+        Ok(self)
+    }
+
+    fn shift(&mut self, axis: Axis, n: isize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::Y => Row::shift(self, n),
+            _ => return Err(missing_axis("Row", axis)),
+        }
+        Ok(self)
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::{ Block, Row };
+    use crate::block::test::TestBlock;
+
+    fn test_row(ids: &[&str]) -> Row<TestBlock> {
+        let mut row = Row::default();
+        for id in ids {
+            row.blocks.push(TestBlock::create(&id.to_string()));
+        }
+        row
+    }
+
+    fn ids(row: &Row<TestBlock>) -> Vec<&str> {
+        row.iter().map(|b| b.id.as_str()).collect()
+    }
+
+    /// Test splitting, stitching, mirroring and riffling.
+    #[test] fn row_transform_test() {
+        let mut row = test_row(&["a", "b", "c"]);
+        let mut rest = row.split(1);
+        assert_eq!(ids(&row), vec!["a"]);
+        assert_eq!(ids(&rest), vec!["b", "c"]);
+        assert!(row.split(5).is_empty());
+
+        row.stitch(&mut rest);
+        assert!(rest.is_empty());
+        row.mirror();
+        assert_eq!(ids(&row), vec!["a", "b", "c", "c", "b", "a"]);
+
+        let mut row = test_row(&["a", "b", "c"]);
+        row.riffle(&mut test_row(&["x", "y"]));
+        assert_eq!(ids(&row), vec!["a", "x", "b", "y"]);
+    }
+
+    /// Test rotating and chunking.
+    #[test] fn row_rotate_test() {
+        let mut row = test_row(&["a", "b", "c"]);
+        row.rotate_left(4);
+        assert_eq!(ids(&row), vec!["b", "c", "a"]);
+        row.rotate_right(1);
+        assert_eq!(ids(&row), vec!["a", "b", "c"]);
+
+        let chunks = row.chunks(2);
+        assert!(row.chunks(0).is_empty());
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(ids(&chunks[1]), vec!["c"]);
+
+        row.shift(-2);
+        assert_eq!(ids(&row), vec!["c", "", ""]);
+        row.roll(-1);
+        assert_eq!(ids(&row), vec!["", "", "c"]);
+
+        let mut empty = Row::<TestBlock>::default();
+        empty.shift(1);
+        empty.rotate_left(1);
+        assert!(empty.chunks(2).is_empty());
+    }
+
+}
@@ -76,7 +76,6 @@ impl<B: Block> Row<B> {
 
 /// Check whether the range falls within the total number of blocks.
 fn range_boundary_check_helper(total: usize, start: usize, end: usize) -> bool {
-    if start > end || end >= total { false } 
-    else { true }
+    !(start > end || end >= total)
 }
 
@@ -165,9 +165,6 @@ impl<B: Block> Stack<B> {
 
 
 #[cfg(test)] mod test {
-    use crate::block::{ Block, test::TestBlock };
-    use crate::types::layer::{ Layer, test::test_layer };
-    use crate::types::stack::{ Stack, test::test_stack };
     
     #[test] fn add_block_test() {}
 
@@ -14,7 +14,7 @@ impl<B: Block> Stack<B> {
     /// Add a pre-existing layer to the top of the stack.
     pub fn add_layer(&mut self, mut layer: Layer<B>) -> &mut Self {
         self.layouts_mut().push(layer.layout().clone());
-        self.blocks_mut().append(&mut layer.blocks_mut());
+        self.blocks_mut().append(layer.blocks_mut());
         self
     }
 
@@ -105,18 +105,17 @@ impl<B: Block> Stack<B> {
 
 
 #[cfg(test)] mod test {
-    use crate::block::{ Block, test::TestBlock };
-    use crate::types::layer::{ Layer, test::test_layer };
-    use crate::types::stack::{ Stack, test::test_stack };
+    use crate::types::layer::test::test_layer;
+    use crate::types::stack::test::test_stack;
 
-    ///
+    //
     #[test] fn new_layer_test() {
         let mut stack = test_stack();
         stack.new_layer();
         assert_eq!(stack.layouts[3].len(), 0);
     }
 
-    ///
+    //
     #[test] fn add_layer_test() {
 
         let mut stack = test_stack();
@@ -131,7 +130,7 @@ impl<B: Block> Stack<B> {
 
     }
 
-    ///
+    //
     #[test] fn insert_layer_test() {
 
         let mut stack = test_stack();
@@ -41,7 +41,7 @@ impl<B: Block> Stack<B> {
 
         // Extract row lengths & flatten blocks.
         let mut lengths = rows.iter().map(|r| r.len()).collect();
-        let mut blocks = rows.into_iter().flat_map(|r| r.blocks).into_iter().collect();
+        let mut blocks = rows.into_iter().flat_map(|r| r.blocks).collect();
 
         // Add blocks.
         self.blocks.append(&mut blocks);
@@ -159,9 +159,6 @@ impl<B: Block> Stack<B> {
 
 
 #[cfg(test)] mod test {
-    use crate::block::{ Block, test::TestBlock };
-    use crate::types::layer::{ Layer, test::test_layer };
-    use crate::types::stack::{ Stack, test::test_stack };
 
     #[test] fn add_row_test() {}
 
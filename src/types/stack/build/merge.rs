@@ -0,0 +1,229 @@
+
+use crate::{ Block, Stack };
+use crate::order::BlendMode;
+use crate::order::blend::paste_block;
+use crate::order::coord::flat_coords;
+
+/// Functions for combining stacks:
+impl<B: Block> Stack<B> {
+
+    /// Paste the other stack into this one, with its first block at the given (l, r, b) offset,
+    /// combining blocks that land on existing ones by the blend mode.
+    /// Layers, rows and row positions that do not exist yet are grown with voids.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// use blok::order::BlendMode;
+    ///
+    /// let mut world = Stack::<u8>::new();
+    /// world.populate(vec![layout![2, 2]], &1);
+    /// let mut prefab = Stack::<u8>::new();
+    /// prefab.populate(vec![layout![1]; 2], &2);
+    ///
+    /// world.paste(&prefab, (0, 1, 1), &BlendMode::Overwrite);
+    /// assert_eq!(world.layouts(), &vec![layout![2, 2], layout![0, 2]]);
+    /// assert_eq!(world.blocks(), &vec![1, 1, 1, 2, 0, 2]);
+    /// ```
+    pub fn paste(
+        &mut self,
+        other: &Self,
+        offset: (usize, usize, usize),
+        mode: &BlendMode<B>
+    ) -> &mut Self {
+
+        let mut layers = self.clone_into_blocks();
+
+        for (l, layer) in other.clone_into_blocks().into_iter().enumerate() {
+            let l = l + offset.0;
+            if layers.len() <= l {
+                layers.resize(l + 1, Vec::new());
+            }
+
+            for (r, row) in layer.iter().enumerate() {
+                let r = r + offset.1;
+                if layers[l].len() <= r {
+                    layers[l].resize(r + 1, Vec::new());
+                }
+
+                for (b, block) in row.iter().enumerate() {
+                    paste_block(&mut layers[l][r], b + offset.2, block, mode);
+                }
+            }
+        }
+
+        self.set_from_blocks(layers);
+        self
+    }
+
+    /// Occupy every cell occupied in either stack, treating non-void blocks as occupied,
+    /// with the other stack's first block at the given (l, r, b) offset.
+    /// Cells occupied in both are given the result of the closure,
+    /// called with this stack's block, then the other's.
+    /// Positions that do not exist yet are grown with voids.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    ///
+    /// let mut a = Stack::<u8>::new();
+    /// a.populate(vec![layout![2]], &1);
+    /// let mut b = Stack::<u8>::new();
+    /// b.populate(vec![layout![2]], &2);
+    ///
+    /// let mut union = a.clone();
+    /// union.union(&b, (0, 0, 1), |x, y| x + y);
+    /// assert_eq!(union.blocks(), &vec![1, 3, 2]);
+    ///
+    /// let mut intersection = a.clone();
+    /// intersection.intersection(&b, (0, 0, 1), |x, y| x + y);
+    /// assert_eq!(intersection.blocks(), &vec![0, 3]);
+    ///
+    /// let mut difference = a.clone();
+    /// difference.difference(&b, (0, 0, 1));
+    /// assert_eq!(difference.blocks(), &vec![1, 0]);
+    ///
+    /// let mut xor = a.clone();
+    /// xor.xor(&b, (0, 0, 1));
+    /// assert_eq!(xor.blocks(), &vec![1, 0, 2]);
+    /// ```
+    pub fn union(
+        &mut self,
+        other: &Self,
+        offset: (usize, usize, usize),
+        resolve: impl Fn(&B, &B) -> B
+    ) -> &mut Self {
+
+        self.combine(other, offset, true, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(resolve(a, b)),
+            (a, b) => a.or(b).cloned(),
+        })
+    }
+
+    /// Keep only the cells occupied in both stacks, with the other stack at the given offset.
+    /// Cells occupied in both are given the result of the closure,
+    /// called with this stack's block, then the other's. All other blocks become voids.
+    pub fn intersection(
+        &mut self,
+        other: &Self,
+        offset: (usize, usize, usize),
+        resolve: impl Fn(&B, &B) -> B
+    ) -> &mut Self {
+
+        self.combine(other, offset, false, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(resolve(a, b)),
+            _ => None,
+        })
+    }
+
+    /// Carve the shape of the other stack out of this one, with the other stack at the given offset,
+    /// replacing blocks in cells it occupies with voids.
+    pub fn difference(
+        &mut self,
+        other: &Self,
+        offset: (usize, usize, usize)
+    ) -> &mut Self {
+
+        self.combine(other, offset, false, |a, b| match b {
+            Some(_) => None,
+            None => a.cloned(),
+        })
+    }
+
+    /// Occupy the cells occupied in exactly one of the stacks, with the other stack at the given offset.
+    /// Cells occupied in both become voids, and positions that do not exist yet are grown with voids.
+    pub fn xor(
+        &mut self,
+        other: &Self,
+        offset: (usize, usize, usize)
+    ) -> &mut Self {
+
+        self.combine(other, offset, true, |a, b| match (a, b) {
+            (Some(_), Some(_)) => None,
+            (a, b) => a.or(b).cloned(),
+        })
+    }
+
+    /// Set each cell to the result of the closure, given the non-void blocks of each stack at the cell,
+    /// with the other stack at the given offset. A result of None leaves a void.
+    /// Cells only found in the other stack are visited if `grow` is true,
+    /// and only this stack's cells otherwise.
+    fn combine(
+        &mut self,
+        other: &Self,
+        offset: (usize, usize, usize),
+        grow: bool,
+        cell: impl Fn(Option<&B>, Option<&B>) -> Option<B>
+    ) -> &mut Self {
+
+        let mut layers = self.clone_into_blocks();
+        if grow {
+            for (l, r, b) in flat_coords(other.layouts()) {
+                let (l, r, b) = (l + offset.0, r + offset.1, b + offset.2);
+                if layers.len() <= l {
+                    layers.resize(l + 1, Vec::new());
+                }
+                if layers[l].len() <= r {
+                    layers[l].resize(r + 1, Vec::new());
+                }
+                if layers[l][r].len() <= b {
+                    layers[l][r].resize(b + 1, B::void());
+                }
+            }
+        }
+
+        let others = other.clone_into_blocks();
+        let other_at = |l: usize, r: usize, b: usize| {
+            others.get(l.checked_sub(offset.0)?)?
+                .get(r.checked_sub(offset.1)?)?
+                .get(b.checked_sub(offset.2)?)
+                .filter(|block| !block.is_void())
+        };
+
+        for (l, layer) in layers.iter_mut().enumerate() {
+            for (r, row) in layer.iter_mut().enumerate() {
+                for (b, block) in row.iter_mut().enumerate() {
+                    let this = (!block.is_void()).then_some(&*block);
+                    *block = cell(this, other_at(l, r, b)).unwrap_or_else(B::void);
+                }
+            }
+        }
+
+        self.set_from_blocks(layers);
+        self
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::{ Stack, Layout, layout };
+
+    /// Test set operations between ragged stacks.
+    #[test] fn ragged_set_test() {
+        let mut a = Stack::<u8>::new();
+        a.populate(vec![layout![1, 3]], &1);
+        let mut b = Stack::<u8>::new();
+        b.populate(vec![layout![2]; 2], &2);
+
+        let mut union = a.clone();
+        union.union(&b, (0, 1, 2), |_, y| *y);
+        assert_eq!(union.layouts(), &vec![layout![1, 4], layout![0, 4]]);
+        assert_eq!(union.blocks(), &vec![1, 1, 1, 2, 2, 0, 0, 2, 2]);
+
+        let mut xor = a.clone();
+        xor.xor(&b, (0, 0, 0));
+        assert_eq!(xor.layouts(), &vec![layout![2, 3], layout![2]]);
+        assert_eq!(xor.blocks(), &vec![0, 2, 1, 1, 1, 2, 2]);
+
+        let mut intersection = a.clone();
+        intersection.intersection(&b, (0, 1, 1), |x, _| *x);
+        assert_eq!(intersection.layouts(), &vec![layout![1, 3]]);
+        assert_eq!(intersection.blocks(), &vec![0, 0, 1, 1]);
+
+        // Carving out everything leaves only voids, which compress away.
+        let mut difference = a.clone();
+        difference.difference(&a, (0, 0, 0)).compress_all();
+        assert!(difference.blocks().is_empty());
+    }
+
+}
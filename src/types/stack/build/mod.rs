@@ -8,10 +8,13 @@ pub mod populate;
 /// Methods for using blocks that represent empty space.
 pub mod void;
 
-// Methods for changing a stack's organization by manipulating clones.
-// pub mod transform;
+/// Methods for changing a stack's organization by manipulating clones.
+pub mod transform;
 
-// Methods for combining stacks.
-// pub mod merge;
+/// Methods for combining stacks.
+pub mod merge;
+
+/// Methods for changing a stack's resolution.
+pub mod scale;
 
 
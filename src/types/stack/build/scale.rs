@@ -0,0 +1,154 @@
+
+use crate::{ Block, Stack, Coord };
+
+/// Functions for changing resolution:
+impl<B: Block> Stack<B> {
+
+    /// Replace every block with a cube of clones, k blocks along each side.
+    /// Each layer becomes k layers, each row k rows, and each block k blocks,
+    /// so ragged layouts keep their shape at the larger scale.
+    /// Returns an error if k is 0.
+    pub fn upscale(&mut self, k: usize) -> anyhow::Result<&mut Self> {
+        self.upscale_with(k, |block, _| block.clone())
+    }
+
+    /// Replace every block with a cube of blocks created by the closure, k blocks along each side.
+    /// The closure is given the original block and the position within its cube, as (l, r, b).
+    /// Returns an error if k is 0.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    ///
+    /// let mut stack = Stack::<u8>::new();
+    /// stack.populate(vec![layout![1, 2]], &1);
+    /// stack.upscale_with(2, |block, (l, _, b)| block + (l + b) as u8).unwrap();
+    ///
+    /// assert_eq!(stack.layouts(), &vec![layout![2, 2, 4, 4]; 2]);
+    /// assert_eq!(stack.get_block_ref(1, 2, 3), Some(&3));
+    /// ```
+    pub fn upscale_with(
+        &mut self,
+        k: usize,
+        f: impl Fn(&B, Coord) -> B
+    ) -> anyhow::Result<&mut Self> {
+
+        if k == 0 {
+            return Err(anyhow::anyhow!("Scale factor must be at least 1"))
+        }
+
+        let mut layers = Vec::new();
+        for layer in self.clone_into_blocks() {
+            for dl in 0..k {
+                let mut rows = Vec::new();
+                for row in layer.iter() {
+                    for dr in 0..k {
+                        let blocks: Vec<B> = row.iter()
+                            .flat_map(|block| (0..k).map(move |db| (block, db)))
+                            .map(|(block, db)| f(block, (dl, dr, db)))
+                            .collect();
+                        rows.push(blocks);
+                    }
+                }
+                layers.push(rows);
+            }
+        }
+
+        self.set_from_blocks(layers);
+        Ok(self)
+    }
+
+    /// Reduce each cube of blocks, k blocks along each side, to a single block with the closure.
+    /// Cubes at the edges of the stack, or in ragged layouts, may be partial,
+    /// so the closure is given only the blocks present, in layer, row, then block order.
+    /// Returns an error if k is 0.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    ///
+    /// let mut stack = Stack::<u8>::new();
+    /// stack.populate(vec![layout![3, 3]; 2], &1);
+    /// *stack.get_block_mut(0, 0, 2).unwrap() = 7;
+    ///
+    /// // Keep the largest value in each cube.
+    /// stack.downsample(2, |blocks| blocks.iter().map(|b| **b).max().unwrap()).unwrap();
+    /// assert_eq!(stack.layouts(), &vec![layout![2]]);
+    /// assert_eq!(stack.blocks(), &vec![1, 7]);
+    /// ```
+    pub fn downsample(
+        &mut self,
+        k: usize,
+        reducer: impl Fn(&[&B]) -> B
+    ) -> anyhow::Result<&mut Self> {
+
+        if k == 0 {
+            return Err(anyhow::anyhow!("Scale factor must be at least 1"))
+        }
+
+        let source = self.clone_into_blocks();
+        let layers = source.chunks(k)
+            .map(|layers| {
+                let rows = layers.iter()
+                    .map(|layer| layer.len().div_ceil(k))
+                    .max()
+                    .unwrap_or(0);
+
+                (0..rows)
+                    .map(|r| {
+                        let cube_rows: Vec<&Vec<B>> = layers.iter()
+                            .flat_map(|layer| layer.iter().skip(r * k).take(k))
+                            .collect();
+                        let len = cube_rows.iter()
+                            .map(|row| row.len().div_ceil(k))
+                            .max()
+                            .unwrap_or(0);
+
+                        (0..len)
+                            .map(|b| {
+                                let blocks: Vec<&B> = cube_rows.iter()
+                                    .flat_map(|row| row.iter().skip(b * k).take(k))
+                                    .collect();
+                                reducer(&blocks)
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.set_from_blocks(layers);
+        Ok(self)
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::{ Stack, Layout, layout };
+
+    /// Test that downsampling an upscaled ragged stack restores it.
+    #[test] fn scale_round_trip_test() {
+        let mut stack = Stack::<u8>::new();
+        stack.populate(vec![layout![1, 3], layout![2]], &1);
+        *stack.get_block_mut(1, 0, 1).unwrap() = 4;
+        let original = stack.clone();
+
+        stack.upscale(3).unwrap();
+        assert_eq!(stack.blocks().len(), original.blocks().len() * 27);
+
+        // Average each cube.
+        stack.downsample(3, |blocks| {
+            let sum: usize = blocks.iter().map(|b| **b as usize).sum();
+            (sum / blocks.len()) as u8
+        }).unwrap();
+        assert_eq!(stack.layouts(), original.layouts());
+        assert_eq!(stack.blocks(), original.blocks());
+
+        // Partial cubes at the edges are reduced from the blocks present.
+        stack.downsample(2, |blocks| blocks.len() as u8).unwrap();
+        assert_eq!(stack.layouts(), &vec![layout![2]]);
+        assert_eq!(stack.blocks(), &vec![5, 1]);
+        assert!(stack.upscale(0).is_err());
+    }
+
+}
@@ -1,5 +1,7 @@
 
-use super::*;
+use crate::{ Block, Layer, Stack };
+use crate::order::{ Axis, AxisTransform };
+use crate::order::axis::roll_slice;
 
 //
 // TODO: 
@@ -41,9 +43,11 @@ impl<B: Block> Stack<B> {
         stack
     }
 
-    /// Split the stack in two at the given layer.
+    /// Split the stack in two at the given layer. Leaves the original in place,
+    /// and returns the remainder, which is empty if the layer is past the top of the stack.
     pub fn split_z(&mut self, split: usize) -> Self {
         let mut old = self.clone_into_layers();
+        let split = split.min(old.len());
         let new = old.split_off(split);
 
         self.set_from_layers(old);
@@ -59,7 +63,7 @@ impl<B: Block> Stack<B> {
         flipped.iter_mut()
             .for_each(|s| s.flip_x() );
 
-        self.set_from_layers(flipped)
+        self.set_from_layers(flipped);
     }
 
     /// Flip each layer across the X axis, reversing the order of blocks within the rows.
@@ -68,7 +72,7 @@ impl<B: Block> Stack<B> {
         flipped.iter_mut()
             .for_each(|s| s.flip_y() );
 
-        self.set_from_layers(flipped)
+        self.set_from_layers(flipped);
     }
 
     /// Reverses the order of stack layers.
@@ -78,7 +82,7 @@ impl<B: Block> Stack<B> {
             .rev()
             .collect();
 
-        self.set_from_layers(flipped)
+        self.set_from_layers(flipped);
     }
 
     /// Stitch each layer to the corresponding layer in the other stack.
@@ -87,10 +91,10 @@ impl<B: Block> Stack<B> {
         let those = other.clone_into_layers();
 
         these.iter_mut()
-            .zip(those.into_iter())
+            .zip(those)
             .for_each(|(s, mut o)| s.stitch_x(&mut o) );
 
-        self.set_from_layers(these)
+        self.set_from_layers(these);
     }
 
     /// Stitch rows from each layer to the corresponding layer in the other stack.
@@ -99,10 +103,10 @@ impl<B: Block> Stack<B> {
         let those = other.clone_into_layers();
 
         these.iter_mut()
-            .zip(those.into_iter())
+            .zip(those)
             .for_each(|(s, mut o)| s.stitch_y(&mut o) );
 
-        self.set_from_layers(these)
+        self.set_from_layers(these);
     }
 
     /// Stack the entire other stack atop this stack's layers.
@@ -139,10 +143,10 @@ impl<B: Block> Stack<B> {
         let those = other.clone_into_layers();
 
         these.iter_mut()
-            .zip(those.into_iter())
+            .zip(those)
             .for_each(|(s, mut o)| s.riffle_x(&mut o) );
 
-        self.set_from_layers(these)
+        self.set_from_layers(these);
     }
 
     /// Merge the corresponding layers of two stacks by alternating indices for corresponding rows.
@@ -152,10 +156,10 @@ impl<B: Block> Stack<B> {
         let those = other.clone_into_layers();
 
         these.iter_mut()
-            .zip(those.into_iter())
+            .zip(those)
             .for_each(|(s, mut o)| s.riffle_y(&mut o) );
 
-        self.set_from_layers(these)
+        self.set_from_layers(these);
     }
 
     /// Merge two stacks by alternating layers.
@@ -165,12 +169,269 @@ impl<B: Block> Stack<B> {
         let those = other.clone_into_layers();
 
         let riffled: Vec<Layer<B>> = these.into_iter()
-            .zip(those.into_iter())
+            .zip(those)
             .flat_map(|(r, o)| vec![r, o])
             .collect();
 
-        self.set_from_layers(riffled)
+        self.set_from_layers(riffled);
+    }
+
+    /// Move the rows of each layer n places, toward the end if n is positive,
+    /// wrapping rows that pass one end around to the other.
+    /// Layers are rolled independently.
+    pub fn roll_x(&mut self, n: isize) {
+        let mut layers = self.clone_into_layers();
+        layers.iter_mut()
+            .for_each(|l| l.roll_x(n) );
+
+        self.set_from_layers(layers);
+    }
+
+    /// Move the blocks within each row n places, toward the end if n is positive,
+    /// wrapping blocks that pass one end of their row around to the other.
+    /// Rows are rolled independently.
+    pub fn roll_y(&mut self, n: isize) {
+        let mut layers = self.clone_into_layers();
+        layers.iter_mut()
+            .for_each(|l| l.roll_y(n) );
+
+        self.set_from_layers(layers);
+    }
+
+    /// Move the layers n places, toward the top if n is positive,
+    /// wrapping layers that pass one end around to the other.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// # use blok::Block;
+    /// # #[derive(Default, Clone, Debug, PartialEq)]
+    /// # struct MyBlock(u8);
+    /// # impl Block for MyBlock {
+    /// #     type CreationInstructions = u8;
+    /// #     fn create(n: &u8) -> Self { MyBlock(*n) }
+    /// #     fn void() -> Self { MyBlock(0) }
+    /// #     fn is_void(&self) -> bool { self.0 == 0 }
+    /// # }
+    ///
+    /// let mut stack = Stack::<MyBlock>::new();
+    /// stack.populate(vec![layout![1], layout![2]], &1);
+    /// stack.get_block_mut(1, 0, 1).unwrap().0 = 2;
+    ///
+    /// let mut rolled = stack.clone();
+    /// rolled.roll_z(1);
+    /// assert_eq!(rolled.layouts(), &vec![layout![2], layout![1]]);
+    /// assert_eq!(rolled.blocks(), &vec![MyBlock(1), MyBlock(2), MyBlock(1)]);
+    ///
+    /// // Shifting keeps the shape of the wrapped layer, but fills it with voids.
+    /// stack.shift_z(1);
+    /// assert_eq!(stack.layouts(), &vec![layout![2], layout![1]]);
+    /// assert_eq!(stack.blocks(), &vec![MyBlock(0), MyBlock(0), MyBlock(1)]);
+    /// ```
+    pub fn roll_z(&mut self, n: isize) {
+        let mut layers = self.clone_into_layers();
+        roll_slice(&mut layers, n);
+
+        self.set_from_layers(layers);
+    }
+
+    /// Move the rows of each layer n places, toward the end if n is positive.
+    /// Rows that pass one end are dropped, and replaced with rows of voids at the other.
+    pub fn shift_x(&mut self, n: isize) {
+        let mut layers = self.clone_into_layers();
+        layers.iter_mut()
+            .for_each(|l| l.shift_x(n) );
+
+        self.set_from_layers(layers);
+    }
+
+    /// Move the blocks within each row n places, toward the end if n is positive.
+    /// Blocks that pass one end of their row are dropped, and replaced with voids at the other.
+    pub fn shift_y(&mut self, n: isize) {
+        let mut layers = self.clone_into_layers();
+        layers.iter_mut()
+            .for_each(|l| l.shift_y(n) );
+
+        self.set_from_layers(layers);
+    }
+
+    /// Move the layers n places, toward the top if n is positive.
+    /// Layers that pass one end are dropped, and replaced with layers of voids at the other.
+    pub fn shift_z(&mut self, n: isize) {
+        let mut layers = self.clone_into_layers();
+        let wrapped = roll_slice(&mut layers, n);
+        layers[wrapped].iter_mut()
+            .for_each(|l| l.blocks_mut().fill(B::void()) );
+
+        self.set_from_layers(layers);
+    }
+
+    /// Repeat the stack the given number of times along each axis, as (layers, rows, blocks).
+    /// A count of 0 leaves the stack empty.
+    pub fn tile(&mut self, counts: (usize, usize, usize)) {
+        self.tile_with(counts, |_, _| {});
+    }
+
+    /// Repeat the stack the given number of times along each axis,
+    /// flipping every other copy along each axis, so that neighboring copies mirror each other.
+    pub fn tile_mirrored(&mut self, counts: (usize, usize, usize)) {
+        self.tile_with(counts, |(i, j, k), tile| {
+            if i % 2 == 1 { tile.flip_z() }
+            if j % 2 == 1 { tile.flip_x() }
+            if k % 2 == 1 { tile.flip_y() }
+        });
+    }
+
+    /// Repeat the stack the given number of times along each axis,
+    /// passing each copy to the closure with its tile index before it is placed.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// # use blok::Block;
+    /// # #[derive(Default, Clone, Debug, PartialEq)]
+    /// # struct Cell { tile: (usize, usize, usize) }
+    /// # impl Block for Cell {
+    /// #     type CreationInstructions = ();
+    /// #     fn create(_: &()) -> Self { Self::default() }
+    /// #     fn void() -> Self { Self::default() }
+    /// #     fn is_void(&self) -> bool { false }
+    /// # }
+    ///
+    /// let mut lattice = Stack::<Cell>::new();
+    /// lattice.populate(vec![layout![2, 2]], &());
+    /// lattice.tile_with((2, 1, 3), |index, tile| {
+    ///     *tile = tile.map(|_| Cell { tile: index });
+    /// });
+    ///
+    /// assert_eq!(lattice.layouts(), &vec![layout![6, 6]; 2]);
+    /// assert_eq!(lattice.get_block_ref(1, 0, 5).unwrap().tile, (1, 0, 2));
+    /// ```
+    pub fn tile_with(
+        &mut self,
+        counts: (usize, usize, usize),
+        mut f: impl FnMut((usize, usize, usize), &mut Self)
+    ) {
+        let mut tiled = Self::new();
+        for i in 0..counts.0 {
+            let mut layers = Self::new();
+            for j in 0..counts.1 {
+                let mut rows = Self::new();
+                for k in 0..counts.2 {
+                    let mut tile = self.clone();
+                    f((i, j, k), &mut tile);
+                    if k == 0 { rows = tile } else { rows.stitch_y(&mut tile) }
+                }
+                if j == 0 { layers = rows } else { layers.stitch_x(&mut rows) }
+            }
+            tiled.stitch_z(&mut layers);
+        }
+
+        *self = tiled;
+    }
+
+}
+
+
+/// Transformations along an axis chosen at runtime:
+impl<B: Block> AxisTransform for Stack<B> {
+
+    fn split(&mut self, axis: Axis, at: usize) -> anyhow::Result<Self> {
+        Ok(match axis {
+            Axis::X => self.split_x(at),
+            Axis::Y => self.split_y(at),
+            Axis::Z => self.split_z(at),
+        })
+    }
+
+    fn flip(&mut self, axis: Axis) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.flip_x(),
+            Axis::Y => self.flip_y(),
+            Axis::Z => self.flip_z(),
+        }
+        Ok(self)
+    }
+
+    fn stitch(&mut self, axis: Axis, other: &mut Self) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.stitch_x(other),
+            Axis::Y => self.stitch_y(other),
+            Axis::Z => self.stitch_z(other),
+        }
+        Ok(self)
+    }
+
+    fn mirror(&mut self, axis: Axis) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.mirror_x(),
+            Axis::Y => self.mirror_y(),
+            Axis::Z => self.mirror_z(),
+        }
+        Ok(self)
+    }
+
+    fn riffle(&mut self, axis: Axis, other: &mut Self) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.riffle_x(other),
+            Axis::Y => self.riffle_y(other),
+            Axis::Z => self.riffle_z(other),
+        }
+        Ok(self)
+    }
+
+    fn offset(&mut self, axis: Axis, n: usize) -> anyhow::Result<&mut Self> {
+        Ok(match axis {
+            Axis::X => self.offset_x(n),
+            Axis::Y => self.offset_y(n),
+            Axis::Z => self.offset_z(n),
+        })
+    }
+
+    fn pad(&mut self, axis: Axis, n: usize) -> anyhow::Result<&mut Self> {
+        Ok(match axis {
+            Axis::X => self.pad_x(n),
+            Axis::Y => self.pad_y(n),
+            Axis::Z => self.pad_z(n),
+        })
+    }
+
+    fn roll(&mut self, axis: Axis, n: isize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.roll_x(n),
+            Axis::Y => self.roll_y(n),
+            Axis::Z => self.roll_z(n),
+        }
+        Ok(self)
+    }
+
+    fn shift(&mut self, axis: Axis, n: isize) -> anyhow::Result<&mut Self> {
+        match axis {
+            Axis::X => self.shift_x(n),
+            Axis::Y => self.shift_y(n),
+            Axis::Z => self.shift_z(n),
+        }
+        Ok(self)
     }
 
 }
 
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::order::{ Axis, AxisTransform };
+    use crate::types::stack::test::test_stack;
+
+    /// Test that splitting past the end of any axis leaves the stack whole.
+    #[test] fn split_past_end_test() {
+        let mut stack = test_stack();
+        for axis in Axis::all() {
+            let split = stack.split(axis, 10).unwrap();
+            assert!(split.blocks().is_empty());
+            assert_eq!(stack.blocks().len(), 9);
+        }
+
+        let split = stack.split(Axis::Z, 2).unwrap();
+        assert_eq!(split.layouts().len(), 1);
+        assert_eq!(stack.layouts().len(), 2);
+    }
+
+}
@@ -1,5 +1,8 @@
 
-use crate::{ Block, Stack };
+use crate::{ Block, Stack, Layout, Coord };
+use crate::order::Justify;
+use crate::order::justify::justify_blocks;
+use crate::order::coord::flat_coords;
 
 //
 // TODO:
@@ -10,6 +13,66 @@ use crate::{ Block, Stack };
 /// Functions for working with gaps and empty space:
 impl<B: Block> Stack<B> {
 
+    /// Offset each layer with empty rows.
+    pub fn offset_x(&mut self, offset: usize) -> &mut Self {
+        let mut layers = self.clone_into_layers();
+        for layer in layers.iter_mut() {
+            layer.offset_x(offset);
+        }
+
+        self.set_from_layers(layers);
+        self
+    }
+
+    /// Offset every row with void blocks.
+    pub fn offset_y(&mut self, offset: usize) -> &mut Self {
+        let mut layers = self.clone_into_layers();
+        for layer in layers.iter_mut() {
+            layer.offset_y(offset);
+        }
+
+        self.set_from_layers(layers);
+        self
+    }
+
+    /// Offset the stack with empty layers.
+    pub fn offset_z(&mut self, offset: usize) -> &mut Self {
+        let mut layouts = vec![Layout::new(); offset];
+        layouts.append(self.layouts_mut());
+        *self.layouts_mut() = layouts;
+        self
+    }
+
+    /// Insert empty rows at the end of each layer.
+    pub fn pad_x(&mut self, pad: usize) -> &mut Self {
+        let mut layers = self.clone_into_layers();
+        for layer in layers.iter_mut() {
+            layer.pad_x(pad);
+        }
+
+        self.set_from_layers(layers);
+        self
+    }
+
+    /// Add voids to the end of every row.
+    pub fn pad_y(&mut self, pad: usize) -> &mut Self {
+        let mut layers = self.clone_into_layers();
+        for layer in layers.iter_mut() {
+            layer.pad_y(pad);
+        }
+
+        self.set_from_layers(layers);
+        self
+    }
+
+    /// Add empty layers to the top of the stack.
+    pub fn pad_z(&mut self, pad: usize) -> &mut Self {
+        for _ in 0..pad {
+            self.new_layer();
+        }
+        self
+    }
+
     /// Square off the matrix to the highest row length,
     /// by inserting void blocks into the empty indices.
     pub fn realize_voids(&mut self) -> &mut Self {
@@ -89,6 +152,7 @@ impl<B: Block> Stack<B> {
 
 
     /// Removes voids by layer while preserving non-void block count and ordering.
+    /// Rows left empty are removed, but layers are kept, so that they stay at the same height.
     pub fn compress(&mut self) -> &mut Self {
         let mut layers = self.clone_into_layers();
         for layer in layers.iter_mut() {
@@ -99,6 +163,184 @@ impl<B: Block> Stack<B> {
         self
     }
 
+    /// Removes voids by layer, then removes the layers left empty.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    ///
+    /// let mut stack = Stack::<u8>::new();
+    /// stack.populate(vec![layout![2], layout![1, 1], layout![3]], &1);
+    /// *stack.get_block_mut(1, 0, 0).unwrap() = 0;
+    /// *stack.get_block_mut(1, 1, 0).unwrap() = 0;
+    ///
+    /// stack.compress_all();
+    /// assert_eq!(stack.layouts(), &vec![layout![2], layout![3]]);
+    /// ```
+    pub fn compress_all(&mut self) -> &mut Self {
+        let layers = self.clone_into_layers()
+            .into_iter()
+            .map(|mut layer| {
+                layer.compress();
+                layer
+            })
+            .filter(|layer| !layer.blocks().is_empty())
+            .collect();
+
+        self.set_from_layers(layers);
+        self
+    }
+
+    /// Move the non-void blocks of every row to the start, end, or center,
+    /// padding each row with voids to the length of the longest row in the stack.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    /// use blok::order::Justify;
+    ///
+    /// let mut stack = Stack::<u8>::new();
+    /// stack.populate(vec![layout![1], layout![2, 3]], &1);
+    /// stack.justify(Justify::Center);
+    ///
+    /// assert_eq!(stack.layouts(), &vec![layout![3], layout![3, 3]]);
+    /// assert_eq!(stack.blocks()[..6], [0, 1, 0, 1, 1, 0]);
+    /// ```
+    pub fn justify(&mut self, justify: Justify) -> &mut Self {
+        let width = self.layouts().iter()
+            .flat_map(|layout| layout.iter())
+            .max()
+            .copied()
+            .unwrap_or(0);
+
+        let layers = self.clone_into_blocks()
+            .into_iter()
+            .map(|layer| {
+                layer.into_iter()
+                    .map(|row| justify_blocks(row, width, justify))
+                    .collect()
+            })
+            .collect();
+
+        self.set_from_blocks(layers);
+        self
+    }
+
+    /// Justify the rows of a single layer, padding them to the length of its longest row.
+    pub fn justify_layer(&mut self, l: usize, justify: Justify) -> anyhow::Result<&mut Self> {
+        let mut layers = self.clone_into_layers();
+        let layer = layers.get_mut(l)
+            .ok_or(anyhow::anyhow!("Layer {} is not present in the stack", l))?;
+        layer.justify(justify);
+
+        self.set_from_layers(layers);
+        Ok(self)
+    }
+
+    /// Justify a single row within its own length.
+    pub fn justify_row(&mut self, l: usize, r: usize, justify: Justify) -> anyhow::Result<&mut Self> {
+        let mut layers = self.clone_into_layers();
+        let layer = layers.get_mut(l)
+            .ok_or(anyhow::anyhow!("Layer {} is not present in the stack", l))?;
+        layer.justify_row(r, justify)?;
+
+        self.set_from_layers(layers);
+        Ok(self)
+    }
+
+    /// Find the smallest box holding every non-void block, as its first and last corners.
+    /// Returns None if the stack has no non-void blocks.
+    pub fn bounding_box(&self) -> Option<(Coord, Coord)> {
+        flat_coords(self.layouts())
+            .into_iter()
+            .zip(self.blocks())
+            .filter(|(_, block)| !block.is_void())
+            .fold(None, |bounds, (c, _)| match bounds {
+                None => Some((c, c)),
+                Some((min, max)) => Some((
+                    (c.0.min(min.0), c.1.min(min.1), c.2.min(min.2)),
+                    (c.0.max(max.0), c.1.max(max.1), c.2.max(max.2)),
+                )),
+            })
+    }
+
+    /// Keep only the blocks within the box between the given corners, inclusive,
+    /// moving the first corner to (0, 0, 0).
+    /// Rows that end before the box are kept as empty rows.
+    /// Returns an error if the first corner is past the second.
+    pub fn crop(&mut self, min: Coord, max: Coord) -> anyhow::Result<&mut Self> {
+        if min.0 > max.0 || min.1 > max.1 || min.2 > max.2 {
+            return Err(anyhow::anyhow!("Corner {:?} is past corner {:?}", min, max))
+        }
+
+        let layers = self.clone_into_blocks()
+            .into_iter()
+            .skip(min.0)
+            .take(max.0 - min.0 + 1)
+            .map(|layer| {
+                layer.into_iter()
+                    .skip(min.1)
+                    .take(max.1 - min.1 + 1)
+                    .map(|row| {
+                        row.into_iter()
+                            .skip(min.2)
+                            .take(max.2 - min.2 + 1)
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.set_from_blocks(layers);
+        Ok(self)
+    }
+
+    /// Copy the blocks within the box between the given corners, inclusive,
+    /// into a new stack, leaving this one in place.
+    pub fn cropped(&self, min: Coord, max: Coord) -> anyhow::Result<Self> {
+        let mut stack = self.clone();
+        stack.crop(min, max)?;
+        Ok(stack)
+    }
+
+    /// Remove the void layers and rows before and after the non-void blocks,
+    /// the voids before the first non-void column, and the voids at the end of each row.
+    /// Undoes `offset_*`, `pad_*` and `realize_voids`. A stack of voids is left empty.
+    /// ```
+    /// use blok::{ Stack, Layout, layout };
+    ///
+    /// let mut stack = Stack::<u8>::new();
+    /// stack.populate(vec![layout![1, 2]; 2], &1);
+    /// let original = stack.clone();
+    ///
+    /// stack.offset_z(1).offset_y(2).pad_x(1).pad_z(1).realize_voids();
+    /// assert_eq!(stack.bounding_box(), Some(((1, 0, 2), (2, 1, 3))));
+    ///
+    /// stack.trim();
+    /// assert_eq!(stack.layouts(), original.layouts());
+    /// assert_eq!(stack.blocks(), original.blocks());
+    /// ```
+    pub fn trim(&mut self) -> &mut Self {
+        let Some((min, max)) = self.bounding_box() else {
+            self.set_from_blocks(Vec::new());
+            return self
+        };
+
+        self.crop(min, max)
+            .expect("Bounding box corners are in order");
+
+        let layers = self.clone_into_blocks()
+            .into_iter()
+            .map(|layer| {
+                layer.into_iter()
+                    .map(|mut row| {
+                        while row.last().is_some_and(|block| block.is_void()) { row.pop(); }
+                        row
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.set_from_blocks(layers);
+        self
+    }
+
     /* UNDER CONSTRUCTION 
 
     /// Removes voids by dropping "unsupported" blocks down from higher layers.
@@ -126,3 +368,55 @@ impl<B: Block> Stack<B> {
     }
 */
 }
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::{ Layout, layout };
+    use crate::types::stack::test::test_stack;
+
+    /// Test offsetting a stack along each axis.
+    #[test] fn offset_test() {
+
+        let mut stack = test_stack();
+        stack.offset_y(2);
+        assert_eq!(stack.layouts(), &vec![layout![3, 4]; 3]);
+        assert!(stack.get_block_ref(0, 0, 1).unwrap().id.is_empty());
+        assert!(stack.get_block_ref(1, 1, 1).unwrap().id.is_empty());
+        assert_eq!(stack.get_block_ref(1, 0, 2).unwrap().id, "0");
+        assert_eq!(stack.get_block_ref(2, 1, 3).unwrap().id, "2");
+
+        stack.offset_x(1);
+        assert_eq!(stack.layouts(), &vec![layout![0, 3, 4]; 3]);
+        assert_eq!(stack.get_block_ref(1, 1, 2).unwrap().id, "0");
+
+        stack.offset_z(1);
+        assert_eq!(stack.layouts().len(), 4);
+        assert!(stack.layouts()[0].is_empty());
+        assert_eq!(stack.get_block_ref(2, 1, 2).unwrap().id, "0");
+        assert_eq!(stack.blocks().len(), 21);
+    }
+
+    /// Test padding a stack along each axis.
+    #[test] fn pad_test() {
+
+        let mut stack = test_stack();
+        stack.pad_x(1);
+        assert_eq!(stack.layouts(), &vec![layout![1, 2, 0]; 3]);
+
+        stack.pad_y(1);
+        assert_eq!(stack.layouts(), &vec![layout![2, 3, 1]; 3]);
+        assert_eq!(stack.get_block_ref(0, 0, 0).unwrap().id, "0");
+        assert!(stack.get_block_ref(0, 0, 1).unwrap().id.is_empty());
+        assert_eq!(stack.get_block_ref(2, 1, 1).unwrap().id, "2");
+        assert!(stack.get_block_ref(2, 1, 2).unwrap().id.is_empty());
+
+        stack.pad_z(2);
+        assert_eq!(stack.layouts().len(), 5);
+        assert!(stack.layouts()[4].is_empty());
+        assert_eq!(stack.blocks().len(), 18);
+    }
+
+}
@@ -108,7 +108,7 @@ impl<B: Block> Stack<B> {
                 l.into_iter()
                     .map(|r| {
                         r.into_iter()
-                            .map(|b| b.clone())
+                            .cloned()
                             .collect()
                     })
                     .collect()
@@ -155,7 +155,7 @@ impl<B: Block> Stack<B> {
             // then flatten reference vectors into a Vec of clones.
             .flat_map(|r| {
                 r.into_iter()
-                    .map(|b| b.clone())
+                    .cloned()
                     .collect::<Vec<B>>()
             })
             .collect();
@@ -184,7 +184,7 @@ impl<B: Block> Stack<B> {
         // Use partial reference to perform checks & validations.
         let blocks: Vec<B> = self.get_row_ref(l, r)?
             .into_iter()
-            .map(|b| b.clone())
+            .cloned()
             .collect();
 
         // Set a new row using the cloned values.
@@ -210,15 +210,14 @@ impl<B: Block> Stack<B> {
 
 
 #[cfg(test)] mod test {
-    use crate::block::{ Block, test::TestBlock };
-    use crate::types::layer::{ Layer, test::test_layer };
+    use crate::block::test::TestBlock;
     use crate::types::stack::{ Stack, test::test_stack };
     
     //#[test] fn clone_stack_into_blocks_test() {}
     
     //#[test] fn clone_stack_into_rows_test() {}
     
-    ///
+    //
     #[test] fn clone_stack_into_layers_test() {
 
         let mut stack = test_stack();
@@ -240,7 +239,7 @@ impl<B: Block> Stack<B> {
 
     }
 
-    ///
+    //
     #[test] fn layer_partial_clone_test() {
 
         let stack = test_stack();
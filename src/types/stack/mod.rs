@@ -3,6 +3,7 @@ pub mod build;
 pub mod partial;
 pub mod clone;
 pub mod meta;
+pub mod view;
 
 
 use crate::{ Block, Layer, Layout };
@@ -71,10 +72,10 @@ impl<B: Block> Stack<B> {
 }
 
 
-#[cfg(test)] mod test {
-    use crate::Stack;
+#[cfg(test)] pub(crate) mod test {
+    use crate::{ Stack, Layout, layout };
     use crate::block::{ Block, test::TestBlock };
-    use crate::types::layer::{ Layer, test::test_layer };
+    use crate::types::layer::test::test_layer;
     
     pub(crate) fn test_stack() -> Stack<TestBlock> {
         let mut stack = Stack::<TestBlock>::new();
@@ -82,6 +83,21 @@ impl<B: Block> Stack<B> {
         stack
     }
 
+    /// Create a stack of two 2x2 layers, with each block's id set to its index in the stack.
+    pub(crate) fn test_cube() -> Stack<TestBlock> {
+        let mut stack = Stack::<TestBlock>::new();
+        stack.populate_with_clones(vec![layout![2; 2]; 2], &TestBlock::void());
+        number_blocks(&mut stack);
+        stack
+    }
+
+    /// Set each block's id to its index in the stack, so that every block can be told apart.
+    pub(crate) fn number_blocks(stack: &mut Stack<TestBlock>) {
+        for (i, block) in stack.blocks_mut().iter_mut().enumerate() {
+            block.id = i.to_string();
+        }
+    }
+
     #[test] fn new_stack_test() {
         let stack = test_stack();
         assert_eq!(stack.layouts.len(), 3);
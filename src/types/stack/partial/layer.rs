@@ -163,11 +163,9 @@ fn layer_ref_organization_helper<T>(layout: &Layout, mut blocks: Vec<T>) -> Vec<
 
 /*  UNIT TESTS  */
 #[cfg(test)] mod test {
-    use crate::block::{ Block, test::TestBlock };
-    use crate::types::layer::{ Layer, test::test_layer };
-    use crate::types::stack::{ Stack, test::test_stack };
+    use crate::types::stack::test::test_stack;
 
-    ///
+    //
     #[test] fn find_layer_bounds_test() {
 
         let mut stack = test_stack();
@@ -201,7 +199,7 @@ fn layer_ref_organization_helper<T>(layout: &Layout, mut blocks: Vec<T>) -> Vec<
 
     }
 
-    ///
+    //
     #[test] fn get_layer_test() {
 
         let mut stack = test_stack();
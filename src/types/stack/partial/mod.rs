@@ -90,7 +90,7 @@ fn range_boundary_check_helper(total: usize, start: usize, end: usize) -> Option
 /// so that reference reflects stack structure.
 /// Returns an empty Vec when the stack is empty.
 fn collection_organization_helper<T>(
-    layouts: &Vec<Layout>,
+    layouts: &[Layout],
     mut blocks_ref: Vec<T>,
 ) -> Vec<Vec<Vec<T>>> {
 
@@ -120,11 +120,9 @@ fn collection_organization_helper<T>(
 
 
 #[cfg(test)] mod test {
-    use crate::block::{ Block, test::TestBlock };
-    use crate::types::layer::{ Layer, test::test_layer };
-    use crate::types::stack::{ Stack, test::test_stack };
+    use crate::types::stack::test::test_stack;
  
-    ///
+    //
     #[test] fn get_range_test() {
 
         let mut stack = test_stack();
@@ -141,10 +139,10 @@ fn collection_organization_helper<T>(
 
     }
 
-    ///
+    //
     #[test] fn get_all_test() {
 
-        let mut stack = test_stack();
+        let stack = test_stack();
 
         let stack_ref = stack.get_all_ref();
         assert_eq!(stack_ref.len(), stack.layouts.len());
@@ -0,0 +1,339 @@
+
+use crate::{ Block, Stack, Coord };
+
+/// A read-only view of a stack under a composed transform, made without copying blocks.
+/// The view covers the bounding box of the stack: its layer count, longest layer, and longest row.
+/// Positions that do not hold a block in the stack, such as the ends of short rows,
+/// or those moved outside of the box by an offset, are empty.
+/// Axes follow the stack transforms: z is the layer, x is the row, and y is the block in the row.
+/// ```
+/// use blok::{ Stack, StackView, Layout, layout };
+/// # use blok::Block;
+/// # #[derive(Default, Clone, Debug, PartialEq)]
+/// # struct MyBlock(u8);
+/// # impl Block for MyBlock {
+/// #     type CreationInstructions = u8;
+/// #     fn create(n: &u8) -> Self { MyBlock(*n) }
+/// #     fn void() -> Self { MyBlock(0) }
+/// #     fn is_void(&self) -> bool { self.0 == 0 }
+/// # }
+///
+/// let mut stack = Stack::<MyBlock>::new();
+/// stack.populate(vec![layout![2, 2]], &1);
+/// stack.get_block_mut(0, 0, 1).unwrap().0 = 2;
+///
+/// // A quarter turn within the layer moves the block from the end of the first row
+/// // to the end of the last row.
+/// let view = StackView::new(&stack).rotate_z(1);
+/// assert_eq!(view.get(0, 1, 1), Some(&MyBlock(2)));
+///
+/// let mut flipped = stack.clone();
+/// flipped.flip_y();
+/// assert_eq!(StackView::new(&stack).flip_y().materialize().blocks(), flipped.blocks());
+/// ```
+#[derive(Debug, Clone)]
+pub struct StackView<'s, B: Block> {
+    stack: &'s Stack<B>,
+    /// Start index and length of each row, by layer.
+    rows: Vec<Vec<(usize, usize)>>,
+    /// Extent of the stack along each axis, as (layers, rows, blocks).
+    dims: [usize; 3],
+    /// Stack axis shown along each view axis.
+    perm: [usize; 3],
+    /// Whether each view axis runs backward along its stack axis.
+    flip: [bool; 3],
+    /// Position along the stack axis shown at the start of each view axis.
+    start: [isize; 3],
+    /// Length of each view axis, which falls below zero if an offset hides every position.
+    extent: [isize; 3],
+}
+
+impl<'s, B: Block> StackView<'s, B> {
+
+    /// Create an untransformed view of the stack.
+    pub fn new(stack: &'s Stack<B>) -> Self {
+        let mut start = 0usize;
+        let rows: Vec<Vec<(usize, usize)>> = stack.layouts().iter()
+            .map(|layout| {
+                layout.iter()
+                    .map(|&len| {
+                        start += len;
+                        (start - len, len)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let dims = [
+            rows.len(),
+            rows.iter().map(|layer| layer.len()).max().unwrap_or(0),
+            rows.iter().flatten().map(|&(_, len)| len).max().unwrap_or(0),
+        ];
+
+        Self {
+            stack,
+            rows,
+            dims,
+            perm: [0, 1, 2],
+            flip: [false; 3],
+            start: [0; 3],
+            extent: dims.map(|dim| dim as isize),
+        }
+    }
+
+    /// Extent of the view along each axis, as (layers, rows, blocks), including offsets.
+    pub fn dims(&self) -> Coord {
+        let extent = |i: usize| self.extent[i].max(0) as usize;
+        (extent(0), extent(1), extent(2))
+    }
+
+    /// Reverse the order of layers.
+    pub fn flip_z(self) -> Self {
+        self.flip_axis(0)
+    }
+
+    /// Reverse the order of rows within each layer.
+    pub fn flip_x(self) -> Self {
+        self.flip_axis(1)
+    }
+
+    /// Reverse the order of blocks within each row.
+    pub fn flip_y(self) -> Self {
+        self.flip_axis(2)
+    }
+
+    /// Swap rows with blocks, so that each row of the view is a column of the stack.
+    pub fn transpose_xy(self) -> Self {
+        self.permute([0, 2, 1])
+    }
+
+    /// Swap layers with rows.
+    pub fn transpose_xz(self) -> Self {
+        self.permute([1, 0, 2])
+    }
+
+    /// Swap layers with blocks.
+    pub fn transpose_yz(self) -> Self {
+        self.permute([2, 1, 0])
+    }
+
+    /// Show the stack axes along the view axes in the given order,
+    /// so that view axis `i` shows the current view axis `order[i]`.
+    /// Axes are numbered 0 for layers, 1 for rows, and 2 for blocks.
+    /// Leaves the view unchanged if the order is not a permutation of the axes.
+    pub fn permute(mut self, order: [usize; 3]) -> Self {
+        let mut sorted = order;
+        sorted.sort();
+        if sorted != [0, 1, 2] {
+            return self
+        }
+
+        self.perm = order.map(|i| self.perm[i]);
+        self.flip = order.map(|i| self.flip[i]);
+        self.start = order.map(|i| self.start[i]);
+        self.extent = order.map(|i| self.extent[i]);
+        self
+    }
+
+    /// Turn each layer a quarter turn the given number of times, from rows toward blocks.
+    pub fn rotate_z(self, turns: usize) -> Self {
+        self.rotate_plane(1, 2, turns)
+    }
+
+    /// Turn the stack about the rows' axis a quarter turn the given number of times,
+    /// from layers toward blocks.
+    pub fn rotate_x(self, turns: usize) -> Self {
+        self.rotate_plane(0, 2, turns)
+    }
+
+    /// Turn the stack about the blocks' axis a quarter turn the given number of times,
+    /// from layers toward rows.
+    pub fn rotate_y(self, turns: usize) -> Self {
+        self.rotate_plane(0, 1, turns)
+    }
+
+    /// Shift the view by the given number of (layers, rows, blocks).
+    /// Positive offsets leave empty positions at the start of an axis,
+    /// and negative offsets hide the positions they move past the start.
+    pub fn offset(mut self, l: isize, r: isize, b: isize) -> Self {
+        for (i, shift) in [l, r, b].into_iter().enumerate() {
+            self.start[i] -= if self.flip[i] { -shift } else { shift };
+            self.extent[i] += shift;
+        }
+        self
+    }
+
+    /// Get a reference to the block shown at the given position, if there is one.
+    pub fn get(&self, l: usize, r: usize, b: usize) -> Option<&'s B> {
+        let (sl, sr, sb) = self.source(l, r, b)?;
+        let &(start, len) = self.rows.get(sl)?.get(sr)?;
+        (sb < len).then(|| &self.stack.blocks()[start + sb])
+    }
+
+    /// Find the stack coordinates of the block shown at the given position.
+    /// Returns None if the position is outside of the stack's bounding box.
+    pub fn source(&self, l: usize, r: usize, b: usize) -> Option<Coord> {
+        let mut source = [0usize; 3];
+        for (i, v) in [l, r, b].into_iter().enumerate() {
+            let v = v as isize;
+            if v >= self.extent[i] {
+                return None
+            }
+            let u = if self.flip[i] { self.start[i] - v } else { self.start[i] + v };
+            if u < 0 || u as usize >= self.dims[self.perm[i]] {
+                return None
+            }
+            source[self.perm[i]] = u as usize;
+        }
+        Some((source[0], source[1], source[2]))
+    }
+
+    /// Iterate over the blocks shown in the view, with their view coordinates,
+    /// in layer, row, then block order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &'s B)> + '_ {
+        let (dl, dr, db) = self.dims();
+        (0..dl)
+            .flat_map(move |l| (0..dr).flat_map(move |r| (0..db).map(move |b| (l, r, b))))
+            .filter_map(|(l, r, b)| Some(((l, r, b), self.get(l, r, b)?)))
+    }
+
+    /// Copy the view into an owned stack.
+    /// Empty positions before the last block of a row are filled with voids,
+    /// and rows are kept up to the last row in each layer that holds a block.
+    pub fn materialize(&self) -> Stack<B> {
+        let (dl, dr, db) = self.dims();
+        let blocks = (0..dl)
+            .map(|l| {
+                let mut layer: Vec<Vec<B>> = (0..dr)
+                    .map(|r| {
+                        let mut row: Vec<Option<&B>> = (0..db)
+                            .map(|b| self.get(l, r, b))
+                            .collect();
+                        while row.last().is_some_and(|b| b.is_none()) { row.pop(); }
+                        row.into_iter()
+                            .map(|b| b.cloned().unwrap_or_else(B::void))
+                            .collect()
+                    })
+                    .collect();
+                while layer.last().is_some_and(|row| row.is_empty()) { layer.pop(); }
+                layer
+            })
+            .collect();
+
+        let mut stack = Stack::new();
+        stack.set_from_blocks(blocks);
+        stack
+    }
+
+    /// Reverse a view axis, so that its last position becomes its first.
+    fn flip_axis(mut self, i: usize) -> Self {
+        let last = self.extent[i] - 1;
+        self.start[i] += if self.flip[i] { -last } else { last };
+        self.flip[i] = !self.flip[i];
+        self
+    }
+
+    /// Make quarter turns in the plane of two view axes, from the first toward the second.
+    fn rotate_plane(mut self, a: usize, b: usize, turns: usize) -> Self {
+        for _ in 0..turns % 4 {
+            let mut order = [0, 1, 2];
+            order.swap(a, b);
+            self = self.permute(order).flip_axis(b);
+        }
+        self
+    }
+
+}
+
+impl<B: Block> Stack<B> {
+
+    /// Create an untransformed read-only view of the stack.
+    pub fn view(&self) -> StackView<'_, B> {
+        StackView::new(self)
+    }
+
+}
+
+
+
+/*  UNIT TESTS  */
+#[cfg(test)] mod test {
+
+    use crate::{ Stack, StackView, Layout, layout };
+    use crate::block::test::TestBlock;
+    use crate::types::stack::test::{ test_stack, number_blocks };
+
+
+    fn ids(stack: &Stack<TestBlock>) -> Vec<&str> {
+        stack.blocks().iter().map(|b| b.id.as_str()).collect()
+    }
+
+    /// Test that views match the owned transforms.
+    #[test] fn view_matches_transform_test() {
+        let mut stack = test_stack();
+        number_blocks(&mut stack);
+        assert_eq!(ids(&stack.view().materialize()), ids(&stack));
+
+        for (view, transform) in [
+            (stack.view().flip_x(), Stack::flip_x as fn(&mut Stack<TestBlock>)),
+            (stack.view().flip_y(), Stack::flip_y),
+            (stack.view().flip_z(), Stack::flip_z),
+        ] {
+            let mut owned = stack.clone();
+            owned.realize_voids();
+            transform(&mut owned);
+            let mut materialized = view.materialize();
+            materialized.realize_voids();
+            assert_eq!(ids(&materialized), ids(&owned));
+        }
+    }
+
+    /// Test rotations, transposes and offsets.
+    #[test] fn view_rotate_test() {
+        let mut stack = test_stack();
+        number_blocks(&mut stack);
+        let view = stack.view().rotate_z(1);
+        assert_eq!(view.dims(), (3, 2, 2));
+        assert_eq!(view.get(1, 0, 1).unwrap().id, "3");
+        assert_eq!(view.get(0, 1, 0).unwrap().id, "2");
+        assert_eq!(view.get(0, 1, 1), None);
+
+        let view = stack.view().rotate_z(4).transpose_xz().transpose_xz();
+        assert_eq!(ids(&view.materialize()), ids(&stack));
+
+        let view = stack.view().rotate_x(2);
+        assert_eq!(view.get(0, 0, 0), None);
+        assert_eq!(view.get(0, 1, 0).unwrap().id, "8");
+
+        let view = stack.view().offset(1, 0, -1);
+        assert_eq!(view.dims(), (4, 2, 1));
+        assert!(view.get(0, 1, 0).is_none());
+        assert_eq!(view.get(1, 1, 0).unwrap().id, "2");
+        assert_eq!(view.iter().count(), 3);
+        assert_eq!(view.materialize().layouts()[3], layout![0, 1]);
+    }
+
+    /// Test that offsets, flips and rotations apply in the order they are called.
+    #[test] fn view_composition_order_test() {
+        let mut stack = Stack::<u8>::new();
+        stack.populate(vec![layout![3]], &1);
+        *stack.get_block_mut(0, 0, 0).unwrap() = 5;
+        let row = |view: &StackView<u8>| {
+            (0..view.dims().2)
+                .map(|b| view.get(0, 0, b).copied().unwrap_or(0))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(row(&stack.view().offset(0, 0, 2).flip_y()), vec![1, 1, 5, 0, 0]);
+        assert_eq!(row(&stack.view().flip_y().offset(0, 0, 2)), vec![0, 0, 1, 1, 5]);
+        assert_eq!(row(&stack.view().offset(0, 0, -1).flip_y()), vec![1, 1]);
+
+        // A half turn after an offset moves the empty positions to the other end.
+        let view = stack.view().offset(0, 0, 1).rotate_z(2);
+        assert_eq!(view.dims(), (1, 1, 4));
+        assert_eq!(row(&view), vec![1, 1, 5, 0]);
+        assert_eq!(view.source(0, 0, 2), Some((0, 0, 0)));
+    }
+
+}
+
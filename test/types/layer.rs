@@ -80,6 +80,7 @@ use crate::block::TestBlock1;
 
 
 // partial
+#[allow(unused_variables)]
 #[test] fn partial_layer_reference_test() {
     let mut layer = Layer::<TestBlock1>::default();
     layer.populate(layout![4], &"test".to_string());
@@ -110,6 +111,7 @@ use crate::block::TestBlock1;
     assert!(layer.get_block_mut(1, 0).is_none());
 }
 
+#[allow(dead_code)]
 fn insert_row_test() {
     //
 }
@@ -3,6 +3,7 @@ use blok::{ Block, Layout, layout, Stack };
 use crate::block::TestBlock1;
 
 
+#[allow(unused_variables)]
 #[test] fn new_stack_test() {
     let stack = Stack::<TestBlock1>::new();
     let layouts = stack.layouts();